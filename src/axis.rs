@@ -1,16 +1,19 @@
 //! Coordinate axis
 
+mod format;
 mod grid;
+pub use self::format::TicFormat;
 pub use self::grid::Gridline;
 
-use std::borrow::Cow;
 use std::iter::IntoIterator;
 
+use crate::text::{escape, quote, Markup};
 use crate::traits::Data;
-use crate::{Default, Display, Script};
+use crate::{Default, Display, Script, Text};
 
 /// A coordinate axis
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub enum Axis {
     /// X axis on the bottom side of the figure
     BottomX,
@@ -38,6 +41,7 @@ impl Axis {
 /// A pair of axes that define a coordinate system.
 #[allow(missing_docs)]
 #[derive(Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub enum Axes {
     BottomXLeftY,
     BottomXRightY,
@@ -45,17 +49,42 @@ pub enum Axes {
     TopXRightY,
 }
 
+impl Axes {
+    /// Splits the coordinate system into its horizontal and vertical axes
+    pub(crate) fn split(self) -> (Axis, Axis) {
+        use crate::Axes::*;
+        use crate::Axis::*;
+
+        match self {
+            BottomXLeftY => (BottomX, LeftY),
+            BottomXRightY => (BottomX, RightY),
+            TopXLeftY => (TopX, LeftY),
+            TopXRightY => (TopX, RightY),
+        }
+    }
+}
+
 /// Axis range
 ///
 /// Used by [`AxisProperties::range`].
 ///
 /// [`AxisProperties::range`]: struct.AxisProperties.html#method.range
 #[derive(Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub enum Range {
-    /// Autoscale the axis
+    /// Autoscale the axis, extending the range to the nearest tic marks
     Auto,
+    /// Autoscale the axis to the exact range of the data
+    Fit,
     /// Set the limits of the axis
     Limits(f64, f64),
+    /// Set the upper limit of the axis, the lower limit is autoscaled
+    Max(f64),
+    /// Set the lower limit of the axis, the upper limit is autoscaled
+    Min(f64),
+    /// Autoscale the axis to the range of the data, plus a margin on each side. The margin is
+    /// given as a fraction of the range of the data
+    Padded(f64),
 }
 
 /// Axis scale.
@@ -63,11 +92,31 @@ pub enum Range {
 /// Used by [`AxisProperties::scale`].
 ///
 /// [`AxisProperties::scale`]: struct.AxisProperties.html#method.scale
-#[allow(missing_docs)]
 #[derive(Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub enum Scale {
+    /// Linear scale
     Linear,
+    /// Logarithmic scale in base 10
     Logarithmic,
+    /// Logarithmic scale in a custom base, e.g. `2.` or `std::f64::consts::E`
+    LogarithmicBase(f64),
+    /// Symmetric logarithmic scale, for data that crosses zero. The scale is approximately linear
+    /// within `[-threshold, threshold]` and logarithmic beyond it
+    ///
+    /// **Note** Requires gnuplot 5.2 or newer
+    SymmetricLog(f64),
+}
+
+impl Scale {
+    /// Base of the logarithmic scale, `None` if the scale is not logarithmic
+    pub(crate) fn log_base(self) -> Option<f64> {
+        match self {
+            Scale::Linear | Scale::SymmetricLog(_) => None,
+            Scale::Logarithmic => Some(10.),
+            Scale::LogarithmicBase(base) => Some(base),
+        }
+    }
 }
 
 /// Labels attached to the tics of an axis
@@ -84,15 +133,22 @@ pub struct TicLabels<P, L> {
 ///
 /// [`configure_axis`]: ../struct.Figure.html#method.configure_axis
 #[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(feature = "serde", serde(default = "Default::default"))]
 pub struct AxisProperties {
     major_grid: Gridline,
+    #[cfg_attr(feature = "serde", serde(deserialize_with = "grid::deserialize_minor"))]
     minor_grid: Gridline,
     hidden: bool,
-    label: Option<Cow<'static, str>>,
-    logarithmic: bool,
-    range: Option<(f64, f64)>,
+    label: Option<Text>,
+    #[cfg_attr(feature = "serde", serde(deserialize_with = "crate::de::checked"))]
+    scale: Scale,
+    #[cfg_attr(feature = "serde", serde(deserialize_with = "crate::de::checked"))]
+    range: Range,
+    reverse: bool,
     scale_factor: f64,
-    tics: Option<String>,
+    tic_format: Option<TicFormat>,
+    tics: Option<Vec<(Text, f64)>>,
 }
 
 impl Default for AxisProperties {
@@ -102,9 +158,11 @@ impl Default for AxisProperties {
             minor_grid: Gridline::new(true),
             hidden: false,
             label: None,
-            logarithmic: false,
-            range: None,
+            scale: Scale::Linear,
+            range: Range::Auto,
+            reverse: false,
             scale_factor: 1.,
+            tic_format: None,
             tics: None,
         }
     }
@@ -130,7 +188,7 @@ impl AxisProperties {
     /// Attaches a label to the axis
     pub fn label<S>(&mut self, label: S) -> &mut AxisProperties
     where
-        S: Into<Cow<'static, str>>,
+        S: Into<Text>,
     {
         self.label = Some(label.into());
         self
@@ -139,28 +197,45 @@ impl AxisProperties {
     /// Changes the range of the axis that will be shown
     ///
     /// **Note** All axes are auto-scaled by default
+    ///
+    /// # Panics
+    ///
+    /// Panics if the margin of a `Range::Padded` is a negative value
     pub fn range(&mut self, range: Range) -> &mut AxisProperties {
-        self.hidden = false;
-
-        match range {
-            Range::Auto => self.range = None,
-            Range::Limits(low, high) => self.range = Some((low, high)),
+        if let Range::Padded(margin) = range {
+            assert!(margin >= 0.);
         }
 
+        self.hidden = false;
+        self.range = range;
+        self
+    }
+
+    /// Reverses the direction of the axis
+    ///
+    /// **Note** Axes are not reversed by default
+    pub fn reverse(&mut self, reverse: bool) -> &mut AxisProperties {
+        self.reverse = reverse;
         self
     }
 
     /// Sets the scale of the axis
     ///
     /// **Note** All axes use a linear scale by default
+    ///
+    /// # Panics
+    ///
+    /// Panics if the base of a `LogarithmicBase` scale is non-positive or equal to one, or if the
+    /// threshold of a `SymmetricLog` scale is a non-positive value
     pub fn scale(&mut self, scale: Scale) -> &mut AxisProperties {
-        self.hidden = false;
-
         match scale {
-            Scale::Linear => self.logarithmic = false,
-            Scale::Logarithmic => self.logarithmic = true,
+            Scale::Linear | Scale::Logarithmic => {}
+            Scale::LogarithmicBase(base) => assert!(base > 0. && (base - 1.).abs() > f64::EPSILON),
+            Scale::SymmetricLog(threshold) => assert!(threshold > 0.),
         }
 
+        self.hidden = false;
+        self.scale = scale;
         self
     }
 
@@ -188,19 +263,88 @@ impl AxisProperties {
 
         let pairs = positions
             .into_iter()
-            .zip(labels.into_iter())
-            .map(|(pos, label)| format!("'{}' {}", label.as_ref(), pos.f64()))
+            .zip(labels)
+            .map(|(pos, label)| (Text::plain(label.as_ref().to_owned()), pos.f64()))
             .collect::<Vec<_>>();
 
         if pairs.is_empty() {
             self.tics = None
         } else {
-            self.tics = Some(pairs.join(", "));
+            self.tics = Some(pairs);
         }
 
         self
     }
 
+    /// Formats the tic labels of the axis
+    ///
+    /// The tics are placed on "nice" positions within the range of the axis, which is either the
+    /// range set with [`range`] or, when the axis is auto-scaled, the range of the plotted data.
+    ///
+    /// **Note** Labels attached with [`tick_labels`] take precedence over this format
+    ///
+    /// [`range`]: #method.range
+    /// [`tick_labels`]: #method.tick_labels
+    pub fn tic_format(&mut self, format: TicFormat) -> &mut AxisProperties {
+        self.tic_format = Some(format);
+        self
+    }
+
+    /// Whether the script of this axis depends on the range of the plotted data
+    pub(crate) fn needs_data_range(&self) -> bool {
+        let formats_tics = self.tic_format.is_some() && self.tics.is_none();
+        let is_padded = matches!(self.range, Range::Padded(_));
+
+        !self.hidden && (formats_tics || is_padded)
+    }
+
+    /// The limits of the axis, given the range of the plotted data
+    fn limits(&self, data_range: Option<(f64, f64)>) -> Option<(f64, f64)> {
+        match self.range {
+            Range::Auto | Range::Fit => data_range,
+            Range::Limits(low, high) => Some((low, high)),
+            Range::Max(high) => data_range.map(|(low, _)| (low.min(high), high)),
+            Range::Min(low) => data_range.map(|(_, high)| (low, high.max(low))),
+            Range::Padded(margin) => data_range.map(|(low, high)| {
+                let margin = margin * (high - low);
+
+                (low - margin, high + margin)
+            }),
+        }
+    }
+
+    /// Computes the formatted tic labels of the axis
+    fn formatted_tics(&self, data_range: Option<(f64, f64)>, markup: Markup) -> Option<String> {
+        if self.tics.is_some() {
+            return None;
+        }
+
+        let format = self.tic_format?;
+        let (low, high) = self.limits(data_range)?;
+
+        let pairs = format
+            .positions(low, high, self.scale.log_base())
+            .into_iter()
+            .map(|pos| format!("{} {}", quote(&escape(&format.format(pos), markup)), pos))
+            .collect::<Vec<_>>();
+
+        if pairs.is_empty() {
+            None
+        } else {
+            Some(pairs.join(", "))
+        }
+    }
+
+    /// Fills the properties of the major gridlines that haven't been set with the ones of `grid`
+    pub(crate) fn default_major_grid(&mut self, grid: &Gridline) {
+        self.major_grid = self.major_grid.or(grid);
+    }
+
+    /// Whether the axis uses a logarithmic scale
+    pub(crate) fn is_logarithmic(&self) -> bool {
+        self.scale.log_base().is_some()
+    }
+
     /// Configure the major grid. These grid lines are places on the major tic marks.
     pub fn configure_major_grid<F: FnOnce(&mut Gridline) -> &mut Gridline>(
         &mut self,
@@ -211,6 +355,9 @@ impl AxisProperties {
     }
 
     /// Configure the minor grid. These grid lines are places on the minor tic marks.
+    ///
+    /// **Note** The minor grid is only drawn where the axis shows minor tics, gnuplot only shows
+    /// them on logarithmic axes by default
     pub fn configure_minor_grid<F: FnOnce(&mut Gridline) -> &mut Gridline>(
         &mut self,
         configure: F,
@@ -220,9 +367,11 @@ impl AxisProperties {
     }
 }
 
-impl<'a> Script for (Axis, &'a AxisProperties) {
+/// The axis script, along with the range of the data plotted against it (if the axis needs it)
+/// and the markup of the terminal
+impl Script for (Axis, &AxisProperties, Option<(f64, f64)>, Markup) {
     fn script(&self) -> String {
-        let &(axis, properties) = self;
+        let &(axis, properties, data_range, markup) = self;
         let axis_ = axis.display();
 
         let mut script = if properties.hidden {
@@ -232,21 +381,72 @@ impl<'a> Script for (Axis, &'a AxisProperties) {
         };
 
         if let Some(ref tics) = properties.tics {
+            let pairs = tics
+                .iter()
+                .map(|(label, pos)| format!("{} {}", label.render(markup), pos))
+                .collect::<Vec<_>>();
+
+            script.push_str(&format!("({})", pairs.join(", ")))
+        } else if let Some(tics) = properties.formatted_tics(data_range, markup) {
             script.push_str(&format!("({})", tics))
         }
 
         script.push('\n');
 
         if let Some(ref label) = properties.label {
-            script.push_str(&format!("set {}label '{}'\n", axis_, label))
+            script.push_str(&format!("set {}label {}\n", axis_, label.render(markup)))
         }
 
-        if let Some((low, high)) = properties.range {
-            script.push_str(&format!("set {}range [{}:{}]\n", axis_, low, high))
+        let reverse = if properties.reverse { " reverse" } else { "" };
+        match properties.range {
+            Range::Auto => {
+                if properties.reverse {
+                    script.push_str(&format!("set {}range [*:*] reverse\n", axis_))
+                }
+            }
+            Range::Fit => {
+                script.push_str(&format!("set {}range [*:*]{}\n", axis_, reverse));
+                script.push_str(&format!("set autoscale {}fix\n", axis_));
+            }
+            Range::Limits(low, high) => {
+                let (low, high) = if properties.reverse {
+                    (high, low)
+                } else {
+                    (low, high)
+                };
+
+                script.push_str(&format!("set {}range [{}:{}]\n", axis_, low, high))
+            }
+            Range::Max(high) => {
+                script.push_str(&format!("set {}range [*:{}]{}\n", axis_, high, reverse))
+            }
+            Range::Min(low) => {
+                script.push_str(&format!("set {}range [{}:*]{}\n", axis_, low, reverse))
+            }
+            Range::Padded(_) => {
+                if let Some((low, high)) = properties.limits(data_range) {
+                    let (low, high) = if properties.reverse {
+                        (high, low)
+                    } else {
+                        (low, high)
+                    };
+
+                    script.push_str(&format!("set {}range [{}:{}]\n", axis_, low, high))
+                }
+            }
         }
 
-        if properties.logarithmic {
-            script.push_str(&format!("set logscale {}\n", axis_));
+        match properties.scale {
+            Scale::Linear => {}
+            Scale::Logarithmic => script.push_str(&format!("set logscale {}\n", axis_)),
+            Scale::LogarithmicBase(base) => {
+                script.push_str(&format!("set logscale {} {}\n", axis_, base))
+            }
+            Scale::SymmetricLog(threshold) => script.push_str(&format!(
+                "set nonlinear {0} via sgn({0})*log10(1+abs({0})/{1}) \
+                 inverse sgn({0})*{1}*(10**abs({0})-1)\n",
+                axis_, threshold
+            )),
         }
 
         script.push_str(&(axis, &properties.major_grid).script());
@@ -261,3 +461,55 @@ impl crate::ScaleFactorTrait for AxisProperties {
         self.scale_factor
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::{Axis, AxisProperties, Range};
+    use crate::text::Markup;
+    use crate::{Color, Default, Layer, LineType, Script};
+
+    #[test]
+    fn partial_and_reversed_ranges() {
+        let mut properties: AxisProperties = Default::default();
+        properties.range(Range::Min(0.)).reverse(true);
+        let script = (Axis::LeftY, &properties, None, Markup::Enhanced).script();
+        assert!(script.contains("set yrange [0:*] reverse\n"));
+
+        properties.range(Range::Limits(1., 2.));
+        let script = (Axis::LeftY, &properties, None, Markup::Enhanced).script();
+        assert!(script.contains("set yrange [2:1]\n"));
+
+        properties.range(Range::Fit).reverse(false);
+        let script = (Axis::BottomX, &properties, None, Markup::Enhanced).script();
+        assert!(script.contains("set autoscale xfix\n"));
+    }
+
+    #[test]
+    fn padded_range() {
+        let mut properties: AxisProperties = Default::default();
+        properties.range(Range::Padded(0.1));
+        assert!(properties.needs_data_range());
+
+        let script = (
+            Axis::BottomX,
+            &properties,
+            Some((0., 10.)),
+            Markup::Enhanced,
+        )
+            .script();
+        assert!(script.contains("set xrange [-1:11]\n"));
+    }
+
+    #[test]
+    fn styled_grids_on_secondary_axis() {
+        let mut properties: AxisProperties = Default::default();
+        properties
+            .configure_major_grid(|g| g.show().layer(Layer::Back).color(Color::Gray))
+            .configure_minor_grid(|g| g.show().line_type(LineType::Dot).opacity(0.5));
+
+        let script = (Axis::RightY, &properties, None, Markup::Enhanced).script();
+        assert!(script.contains("set grid y2tics back lc rgb 'gray'\n"));
+        assert!(script.contains("set grid my2tics , dt (1,4) lc rgb '#80000000'\n"));
+        assert!(!script.contains("set my2tics"));
+    }
+}
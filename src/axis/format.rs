@@ -0,0 +1,264 @@
+//! Tic label formatters
+
+/// Human readable formatting of the tic labels of an axis.
+///
+/// The tic positions are computed from the range of the axis and each one is labeled using the
+/// selected format.
+///
+/// Used by [`AxisProperties::tic_format`].
+///
+/// [`AxisProperties::tic_format`]: struct.AxisProperties.html#method.tic_format
+#[derive(Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub enum TicFormat {
+    /// Binary byte sizes: `512 B`, `1.5 KiB`, `2 MiB`
+    Bytes,
+    /// Engineering notation, where the exponent is a multiple of 3: `1.5e3`, `250e-6`
+    Engineering,
+    /// Percentages, where `1.0` is labeled as `100%`
+    Percent,
+    /// SI prefixes: `1k`, `2.5M`, `30µ`
+    Si,
+    /// Thousands separators: `1,234,567`
+    Thousands,
+}
+
+const SI_PREFIXES: [(i32, &str); 11] = [
+    (-15, "f"),
+    (-12, "p"),
+    (-9, "n"),
+    (-6, "µ"),
+    (-3, "m"),
+    (0, ""),
+    (3, "k"),
+    (6, "M"),
+    (9, "G"),
+    (12, "T"),
+    (15, "P"),
+];
+
+const BINARY_PREFIXES: [&str; 7] = ["B", "KiB", "MiB", "GiB", "TiB", "PiB", "EiB"];
+
+/// Maximum number of decimals shown on a label
+const PRECISION: usize = 3;
+
+impl TicFormat {
+    /// Formats `value` as a tic label
+    pub fn format(self, value: f64) -> String {
+        if !value.is_finite() {
+            return value.to_string();
+        }
+
+        match self {
+            TicFormat::Bytes => {
+                let (exponent, mantissa) = binary_exponent(value);
+                format!("{} {}", trim(mantissa), BINARY_PREFIXES[exponent])
+            }
+            TicFormat::Engineering => {
+                let exponent = engineering_exponent(value);
+                let mantissa = trim(value / 10f64.powi(exponent));
+
+                if exponent == 0 {
+                    mantissa
+                } else {
+                    format!("{}e{}", mantissa, exponent)
+                }
+            }
+            TicFormat::Percent => format!("{}%", trim(value * 100.)),
+            TicFormat::Si => {
+                let exponent = engineering_exponent(value).clamp(-15, 15);
+                let prefix = SI_PREFIXES
+                    .iter()
+                    .find(|&&(e, _)| e == exponent)
+                    .map_or("", |&(_, prefix)| prefix);
+
+                format!("{}{}", trim(value / 10f64.powi(exponent)), prefix)
+            }
+            TicFormat::Thousands => {
+                let s = trim(value);
+                let (sign, s) = if let Some(s) = s.strip_prefix('-') {
+                    ("-", s)
+                } else {
+                    ("", &*s)
+                };
+                let (integer, fraction) = match s.find('.') {
+                    Some(i) => s.split_at(i),
+                    None => (s, ""),
+                };
+
+                let mut grouped = String::new();
+                for (i, digit) in integer.chars().enumerate() {
+                    if i != 0 && (integer.len() - i) % 3 == 0 {
+                        grouped.push(',');
+                    }
+                    grouped.push(digit);
+                }
+
+                format!("{}{}{}", sign, grouped, fraction)
+            }
+        }
+    }
+
+    /// Computes the position of the tics that cover the `[low, high]` range
+    pub(crate) fn positions(self, low: f64, high: f64, log_base: Option<f64>) -> Vec<f64> {
+        let (low, high) = if low <= high {
+            (low, high)
+        } else {
+            (high, low)
+        };
+
+        if !low.is_finite() || !high.is_finite() {
+            return Vec::new();
+        }
+
+        if let Some(base) = log_base {
+            if high <= 0. {
+                return Vec::new();
+            }
+
+            // Decades make little sense for byte sizes, use the binary prefixes instead
+            let base = match self {
+                TicFormat::Bytes if (base - 10.).abs() < f64::EPSILON => 1024.,
+                _ => base,
+            };
+            let low = if low > 0. { low } else { high / base };
+            let first = low.log(base).ceil() as i32;
+            let last = high.log(base).floor() as i32;
+
+            return (first..=last).map(|e| base.powi(e)).collect();
+        }
+
+        if low == high {
+            return vec![low];
+        }
+
+        let unit = match self {
+            TicFormat::Bytes => 1024f64.powi(binary_exponent(high.abs().max(low.abs())).0 as i32),
+            _ => 1.,
+        };
+        let step = nice_step((high - low) / unit) * unit;
+        let first = (low / step).ceil() as i64;
+        let last = (high / step).floor() as i64;
+
+        (first..=last).map(|i| i as f64 * step).collect()
+    }
+}
+
+/// Number of intervals we aim for between the tics of an axis
+const INTERVALS: f64 = 5.;
+
+/// Picks a step of the form `{1, 2, 5} * 10^n` that splits `span` in about `INTERVALS` parts
+fn nice_step(span: f64) -> f64 {
+    let raw = span / INTERVALS;
+    let magnitude = 10f64.powf(raw.log10().floor());
+    let fraction = raw / magnitude;
+
+    let nice = if fraction < 1.5 {
+        1.
+    } else if fraction < 3. {
+        2.
+    } else if fraction < 7. {
+        5.
+    } else {
+        10.
+    };
+
+    nice * magnitude
+}
+
+/// Exponent, multiple of 3, that leaves a mantissa in `[1, 1000)` once rounded to `PRECISION`
+/// decimals
+fn engineering_exponent(value: f64) -> i32 {
+    if value == 0. {
+        0
+    } else {
+        let exponent = value.abs().log10().floor() as i32;
+        let exponent = exponent.div_euclid(3) * 3;
+
+        if round(value.abs() / 10f64.powi(exponent)) >= 1000. {
+            exponent + 3
+        } else {
+            exponent
+        }
+    }
+}
+
+fn binary_exponent(value: f64) -> (usize, f64) {
+    let mut exponent = 0;
+    let mut mantissa = value;
+
+    while round(mantissa.abs()) >= 1024. && exponent + 1 < BINARY_PREFIXES.len() {
+        mantissa /= 1024.;
+        exponent += 1;
+    }
+
+    (exponent, mantissa)
+}
+
+/// Rounds `value` to `PRECISION` decimals, like `trim` does
+fn round(value: f64) -> f64 {
+    let scale = 10f64.powi(PRECISION as i32);
+
+    (value * scale).round() / scale
+}
+
+/// Formats `value` with up to `PRECISION` decimals, dropping the trailing zeros
+fn trim(value: f64) -> String {
+    let s = format!("{:.*}", PRECISION, value);
+    let s = s.trim_end_matches('0').trim_end_matches('.');
+
+    if s == "-0" {
+        String::from("0")
+    } else {
+        String::from(s)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::TicFormat;
+
+    #[test]
+    fn si() {
+        assert_eq!("0", TicFormat::Si.format(0.));
+        assert_eq!("1k", TicFormat::Si.format(1_000.));
+        assert_eq!("2.5M", TicFormat::Si.format(2_500_000.));
+        assert_eq!("-30µ", TicFormat::Si.format(-30e-6));
+        assert_eq!("500", TicFormat::Si.format(500.));
+        assert_eq!("1k", TicFormat::Si.format(999.9996));
+        assert_eq!("1e3", TicFormat::Engineering.format(999.9996));
+    }
+
+    #[test]
+    fn bytes() {
+        assert_eq!("512 B", TicFormat::Bytes.format(512.));
+        assert_eq!("1.5 KiB", TicFormat::Bytes.format(1536.));
+        assert_eq!("2 MiB", TicFormat::Bytes.format(2. * 1024. * 1024.));
+        assert_eq!("1 KiB", TicFormat::Bytes.format(1023.9999));
+    }
+
+    #[test]
+    fn percent_engineering_and_thousands() {
+        assert_eq!("25%", TicFormat::Percent.format(0.25));
+        assert_eq!("1.5e3", TicFormat::Engineering.format(1_500.));
+        assert_eq!("250e-6", TicFormat::Engineering.format(250e-6));
+        assert_eq!("1,234,567", TicFormat::Thousands.format(1_234_567.));
+        assert_eq!("-1,000.5", TicFormat::Thousands.format(-1_000.5));
+    }
+
+    #[test]
+    fn positions() {
+        assert_eq!(
+            vec![0., 2., 4., 6., 8., 10.],
+            TicFormat::Si.positions(0., 10., None)
+        );
+        assert_eq!(
+            vec![0., 512., 1024., 1536., 2048., 2560., 3072.],
+            TicFormat::Bytes.positions(0., 3500., None)
+        );
+        assert_eq!(
+            vec![1., 10., 100.],
+            TicFormat::Si.positions(0.5, 500., Some(10.))
+        );
+    }
+}
@@ -1,53 +1,190 @@
 //! Gridline
 
-use crate::{Axis, Display, Script};
+use crate::{Axis, Color, Display, Layer, LineType, Script};
 
 /// Gridline properties.
 ///
 /// Modified through [`configure_major_grid`] and [`configure_minor_grid`].
 ///
+/// **Note** gnuplot shares a single major and a single minor grid style between all the axes,
+/// if several axes configure the style of their gridlines the last configured axis wins.
+///
 /// [`configure_major_grid`]: struct.AxisProperties.html#method.configure_major_grid
 /// [`configure_minor_grid`]: struct.AxisProperties.html#method.configure_minor_grid
-#[derive(Clone, Copy)]
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(feature = "serde", serde(default = "Gridline::major"))]
 pub struct Gridline {
+    color: Option<Color>,
+    #[cfg_attr(feature = "serde", serde(skip))]
     is_minor: bool,
-    hidden: bool,
+    hidden: Option<bool>,
+    layer: Option<Layer>,
+    #[cfg_attr(feature = "serde", serde(deserialize_with = "crate::de::checked"))]
+    line_type: Option<LineType>,
+    #[cfg_attr(feature = "serde", serde(deserialize_with = "crate::de::positive"))]
+    linewidth: Option<f64>,
+    #[cfg_attr(feature = "serde", serde(deserialize_with = "crate::de::fraction"))]
+    opacity: Option<f64>,
 }
 
-// TODO Lots of configuration pending: linetype, linewidth, etc
 impl Gridline {
     pub(crate) fn new(is_minor: bool) -> Gridline {
         Gridline {
+            color: None,
             is_minor,
-            hidden: true,
+            hidden: None,
+            layer: None,
+            line_type: None,
+            linewidth: None,
+            opacity: None,
         }
     }
 
+    #[cfg(feature = "serde")]
+    fn major() -> Gridline {
+        Gridline::new(false)
+    }
+
     /// Hides the gridlines
     ///
     /// **Note** Both `Major` and `Minor` gridlines are hidden by default
     pub fn hide(&mut self) -> &mut Gridline {
-        self.hidden = true;
+        self.hidden = Some(true);
         self
     }
 
     /// Shows the gridlines
     pub fn show(&mut self) -> &mut Gridline {
-        self.hidden = false;
+        self.hidden = Some(false);
         self
     }
+
+    /// Sets the color of the gridlines
+    pub fn color(&mut self, color: Color) -> &mut Gridline {
+        self.color = Some(color);
+        self
+    }
+
+    /// Selects whether the gridlines are drawn behind or in front of the data
+    pub fn layer(&mut self, layer: Layer) -> &mut Gridline {
+        self.layer = Some(layer);
+        self
+    }
+
+    /// Changes the line type of the gridlines
+    ///
+    /// **Note** By default gnuplot draws the gridlines with dotted lines
+    ///
+    /// # Panics
+    ///
+    /// Panics if a `Custom` dash pattern is invalid
+    pub fn line_type(&mut self, lt: LineType) -> &mut Gridline {
+        lt.check();
+
+        self.line_type = Some(lt);
+        self
+    }
+
+    /// Changes the width of the gridlines
+    ///
+    /// # Panics
+    ///
+    /// Panics if `width` is a non-positive value
+    pub fn line_width(&mut self, lw: f64) -> &mut Gridline {
+        assert!(lw > 0.);
+
+        self.linewidth = Some(lw);
+        self
+    }
+
+    /// Changes the opacity of the gridlines
+    ///
+    /// **Note** If no color has been set, the opacity is applied to black gridlines
+    ///
+    /// # Panics
+    ///
+    /// Panics if `opacity` is outside the range `[0, 1]`
+    pub fn opacity(&mut self, opacity: f64) -> &mut Gridline {
+        assert!((0. ..=1.).contains(&opacity));
+
+        self.opacity = Some(opacity);
+        self
+    }
+
+    /// Fills the properties that haven't been set with the ones of `default`
+    pub(crate) fn or(&self, default: &Gridline) -> Gridline {
+        Gridline {
+            color: self.color.or(default.color),
+            is_minor: self.is_minor,
+            hidden: self.hidden.or(default.hidden),
+            layer: self.layer.or(default.layer),
+            line_type: self.line_type.clone().or_else(|| default.line_type.clone()),
+            linewidth: self.linewidth.or(default.linewidth),
+            opacity: self.opacity.or(default.opacity),
+        }
+    }
+}
+
+/// Deserializes the minor gridlines, which aren't told apart from the major ones in the
+/// serialized form
+#[cfg(feature = "serde")]
+pub(crate) fn deserialize_minor<'de, D>(deserializer: D) -> Result<Gridline, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let mut gridline = <Gridline as serde::Deserialize>::deserialize(deserializer)?;
+    gridline.is_minor = true;
+    Ok(gridline)
 }
 
-impl<'a> Script for (Axis, &'a Gridline) {
+impl Script for (Axis, &Gridline) {
     fn script(&self) -> String {
         let &(axis, properties) = self;
         let axis = axis.display();
+
+        if properties.hidden.unwrap_or(true) {
+            return String::new();
+        }
+
+        let mut style = String::new();
+
+        if let Some(ref lt) = properties.line_type {
+            style.push_str(&format!("dt {} ", lt.display()))
+        }
+
+        if let Some(lw) = properties.linewidth {
+            style.push_str(&format!("lw {} ", lw))
+        }
+
+        match (properties.color, properties.opacity) {
+            (Some(color), None) => style.push_str(&format!("lc rgb '{}' ", color.display())),
+            (color, Some(opacity)) => style.push_str(&format!(
+                "lc rgb '{}' ",
+                color.unwrap_or(Color::Black).display_with_opacity(opacity)
+            )),
+            (None, None) => {}
+        }
+
         let grid = if properties.is_minor { "m" } else { "" };
+        let mut script = format!("set grid {}{}tics ", grid, axis);
+
+        if let Some(layer) = properties.layer {
+            script.push_str(layer.display());
+            script.push(' ');
+        }
+
+        if !style.is_empty() {
+            // The minor style goes after the major style, separated by a comma
+            if properties.is_minor {
+                script.push_str(", ");
+            }
 
-        if properties.hidden {
-            String::new()
-        } else {
-            format!("set grid {}{}tics\n", grid, axis)
+            script.push_str(&style);
         }
+
+        let mut script = script.trim_end().to_owned();
+        script.push('\n');
+        script
     }
 }
@@ -0,0 +1,317 @@
+//! Renders declarative figure specs
+//!
+//! ```text
+//! ploteria render spec.toml --data results.csv -o chart.svg
+//! ploteria render spec.json --data results.json --dump | gnuplot
+//! ```
+
+use std::collections::HashMap;
+use std::error::Error;
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::process;
+
+use ploteria::spec::{Format, Spec};
+use ploteria::Figure;
+
+const USAGE: &str = "\
+Usage: ploteria render <SPEC> [--data <FILE>] [-o <FILE>] [--dump]
+
+Renders the figure described by the JSON or TOML <SPEC>
+
+Options:
+    --data <FILE>        CSV or JSON file with the data columns named by the spec
+    -o, --output <FILE>  Output file, overrides the one set by the spec
+    --dump               Print the gnuplot script, followed by the data, instead of rendering it
+";
+
+/// Data columns, `None` marks a missing value
+type Columns = HashMap<String, Vec<Option<f64>>>;
+
+struct Args {
+    data: Option<PathBuf>,
+    dump: bool,
+    output: Option<PathBuf>,
+    spec: PathBuf,
+}
+
+fn parse_args<I>(mut args: I) -> Result<Args, String>
+where
+    I: Iterator<Item = String>,
+{
+    match args.next().as_deref() {
+        Some("render") => {}
+        Some(command) => return Err(format!("unknown command `{}`", command)),
+        None => return Err(String::from("missing command")),
+    }
+
+    let (mut data, mut dump, mut output, mut spec) = (None, false, None, None);
+    while let Some(arg) = args.next() {
+        match &*arg {
+            "--data" => data = Some(args.next().ok_or("`--data` needs a file")?.into()),
+            "--dump" => dump = true,
+            "-o" | "--output" => output = Some(args.next().ok_or("`-o` needs a file")?.into()),
+            _ if arg.starts_with('-') => return Err(format!("unknown option `{}`", arg)),
+            _ if spec.is_none() => spec = Some(arg.into()),
+            _ => return Err(format!("unexpected argument `{}`", arg)),
+        }
+    }
+
+    Ok(Args {
+        data,
+        dump,
+        output,
+        spec: spec.ok_or("missing spec file")?,
+    })
+}
+
+fn is_json(path: &Path) -> bool {
+    path.extension().map_or(false, |ext| ext == "json")
+}
+
+/// Reads CSV data, where the first row holds the names of the columns. Empty and non-numeric
+/// cells are missing values
+fn read_csv(csv: &str) -> Result<Columns, String> {
+    let mut lines = csv.lines().filter(|line| !line.trim().is_empty());
+    let names = match lines.next() {
+        Some(header) => split_csv(header),
+        None => return Ok(Columns::new()),
+    };
+
+    let mut columns = vec![Vec::new(); names.len()];
+    for (i, line) in lines.enumerate() {
+        let cells = split_csv(line);
+
+        if cells.len() != names.len() {
+            return Err(format!(
+                "row {} has {} cells, but there are {} columns",
+                i + 2,
+                cells.len(),
+                names.len()
+            ));
+        }
+
+        for (column, cell) in columns.iter_mut().zip(cells) {
+            column.push(cell.parse().ok());
+        }
+    }
+
+    Ok(names.into_iter().zip(columns).collect())
+}
+
+/// Splits a CSV row in its cells, which may be quoted
+fn split_csv(line: &str) -> Vec<String> {
+    let mut cells = vec![String::new()];
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                chars.next();
+                cells.last_mut().unwrap().push('"');
+            }
+            '"' => quoted = !quoted,
+            ',' if !quoted => cells.push(String::new()),
+            _ => cells.last_mut().unwrap().push(c),
+        }
+    }
+
+    cells
+        .into_iter()
+        .map(|cell| cell.trim().to_owned())
+        .collect()
+}
+
+/// Reads JSON data, either an object of columns (`{"x": [1, 2]}`) or an array of records
+/// (`[{"x": 1}, {"x": 2}]`). `null`s are missing values
+fn read_json(json: &str) -> Result<Columns, Box<dyn Error>> {
+    use serde_json::Value;
+
+    let mut columns = Columns::new();
+    match serde_json::from_str(json)? {
+        Value::Object(object) => {
+            for (name, values) in object {
+                columns.insert(name, serde_json::from_value(values)?);
+            }
+        }
+        Value::Array(records) => {
+            for (i, record) in records.iter().enumerate() {
+                let record = record.as_object().ok_or("records must be JSON objects")?;
+
+                for (name, value) in record {
+                    let column = columns.entry(name.clone()).or_default();
+                    column.resize(i, None);
+                    column.push(value.as_f64());
+                }
+            }
+
+            for column in columns.values_mut() {
+                column.resize(records.len(), None);
+            }
+        }
+        _ => return Err("the data must be a JSON object or array".into()),
+    }
+
+    Ok(columns)
+}
+
+/// Builds the figure of the spec, with its data
+fn load(args: &Args) -> Result<Figure, Box<dyn Error>> {
+    let format = if is_json(&args.spec) {
+        Format::Json
+    } else {
+        Format::Toml
+    };
+    let spec = Spec::parse(&fs::read_to_string(&args.spec)?, format)?;
+
+    let columns = match args.data {
+        Some(ref path) if is_json(path) => read_json(&fs::read_to_string(path)?)?,
+        Some(ref path) => read_csv(&fs::read_to_string(path)?)?,
+        None => Columns::new(),
+    };
+
+    let mut figure = spec.into_figure(&columns)?;
+    figure.on_warning(|warning| eprintln!("warning: {}", warning));
+    if let Some(ref output) = args.output {
+        figure.output(output.clone());
+    }
+
+    Ok(figure)
+}
+
+fn render(args: Args) -> Result<(), Box<dyn Error>> {
+    let mut figure = load(&args)?;
+
+    if args.dump {
+        let stdout = io::stdout();
+        let mut stdout = stdout.lock();
+        figure.dump(&mut stdout)?;
+        stdout.flush()?;
+    } else {
+        let gnuplot = figure.draw().map_err(|err| match err.kind() {
+            io::ErrorKind::InvalidData => err.to_string(),
+            _ => format!("couldn't run gnuplot: {}", err),
+        })?;
+        let output = gnuplot.wait_with_output()?;
+        let stderr = String::from_utf8_lossy(&output.stderr);
+
+        if !output.status.success() || !stderr.trim().is_empty() {
+            return Err(format!("gnuplot failed:\n{}", stderr.trim()).into());
+        }
+    }
+
+    Ok(())
+}
+
+fn main() {
+    let args = match parse_args(std::env::args().skip(1)) {
+        Ok(args) => args,
+        Err(err) => {
+            eprintln!("error: {}\n\n{}", err, USAGE);
+            process::exit(2);
+        }
+    };
+
+    if let Err(err) = render(args) {
+        eprintln!("error: {}", err);
+        process::exit(1);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::fs;
+
+    use super::{load, parse_args, read_csv, read_json};
+
+    #[test]
+    fn args() {
+        let args = |args: &str| parse_args(args.split(' ').map(String::from));
+
+        let parsed = args("render spec.toml --data results.csv -o chart.svg --dump").unwrap();
+        assert_eq!("spec.toml", parsed.spec.to_str().unwrap());
+        assert_eq!("results.csv", parsed.data.unwrap().to_str().unwrap());
+        assert_eq!("chart.svg", parsed.output.unwrap().to_str().unwrap());
+        assert!(parsed.dump);
+
+        assert!(args("draw spec.toml").is_err());
+        assert!(args("render").is_err());
+        assert!(args("render spec.toml --data").is_err());
+    }
+
+    #[test]
+    fn csv() {
+        let columns = read_csv("threads,\"mean, ns\"\n1,10.5\n2,\n4,NaN\n").unwrap();
+
+        assert_eq!(vec![Some(1.), Some(2.), Some(4.)], columns["threads"]);
+        assert_eq!(Some(10.5), columns["mean, ns"][0]);
+        assert_eq!(None, columns["mean, ns"][1]);
+        assert!(read_csv("x,y\n1\n").is_err());
+    }
+
+    #[test]
+    fn json() {
+        let columns = read_json(r#"{"x": [1, 2], "y": [3, null]}"#).unwrap();
+        assert_eq!(vec![Some(3.), None], columns["y"]);
+
+        let records = read_json(r#"[{"x": 1, "y": 3}, {"x": 2}]"#).unwrap();
+        assert_eq!(vec![Some(1.), Some(2.)], records["x"]);
+        assert_eq!(vec![Some(3.), None], records["y"]);
+    }
+
+    #[test]
+    fn specs_cant_inject_commands() {
+        let spec = std::env::temp_dir().join(format!("ploteria-{}.json", std::process::id()));
+        fs::write(
+            &spec,
+            r#"{
+                "figure": {
+                    "title": "'\nprint system('id')\n#",
+                    "plots": [{ "script": "with lines\nprint system('id')\n#" }]
+                },
+                "plots": [{ "kind": "Lines", "x": [1, 2], "y": [1, 2] }]
+            }"#,
+        )
+        .unwrap();
+
+        let args = parse_args(vec![String::from("render"), spec.display().to_string()].into_iter());
+        let figure = load(&args.unwrap());
+        fs::remove_file(&spec).unwrap();
+
+        let mut dump = Vec::new();
+        figure.unwrap().dump(&mut dump).unwrap();
+        let script = String::from_utf8_lossy(&dump);
+
+        assert!(!script.contains("\nprint system"));
+        assert_eq!(1, script.matches("with lines").count());
+    }
+
+    #[test]
+    fn missing_values_can_be_rejected() {
+        let dir = std::env::temp_dir();
+        let spec = dir.join(format!("ploteria-missing-{}.toml", std::process::id()));
+        let data = dir.join(format!("ploteria-missing-{}.csv", std::process::id()));
+        fs::write(
+            &spec,
+            "[figure]\nmissing_values = \"Error\"\n\n\
+             [[plots]]\nkind = \"Lines\"\nx = \"x\"\ny = \"y\"\n",
+        )
+        .unwrap();
+        fs::write(&data, "x,y\n1,2\n2,\n").unwrap();
+
+        let args = vec![
+            String::from("render"),
+            spec.display().to_string(),
+            String::from("--data"),
+            data.display().to_string(),
+        ];
+        let figure = load(&parse_args(args.into_iter()).unwrap());
+        fs::remove_file(&spec).unwrap();
+        fs::remove_file(&data).unwrap();
+
+        let err = figure.unwrap().dump(&mut Vec::new()).err().unwrap();
+        assert_eq!(std::io::ErrorKind::InvalidData, err.kind());
+    }
+}
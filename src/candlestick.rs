@@ -1,22 +1,58 @@
 //! "Candlestick" plots
 
 use itertools::izip;
-use std::borrow::Cow;
 use std::iter::IntoIterator;
 
 use crate::data::Matrix;
 use crate::traits::{self, Data};
-use crate::{Color, Default, Display, Figure, LineType, Plot, Script};
+use crate::{
+    Axes, Caps, Color, Column, Coordinate, Default, Display, Figure, LineStyleId, LineType, Plot,
+    Script, Text,
+};
 
 /// Properties common to candlestick plots
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(feature = "serde", serde(default = "Default::default"))]
 pub struct Properties {
+    axes: Option<Axes>,
+    #[cfg_attr(feature = "serde", serde(deserialize_with = "crate::de::checked"))]
+    caps: Caps,
     color: Option<Color>,
-    label: Option<Cow<'static, str>>,
-    line_type: LineType,
+    label: Option<Text>,
+    // The line styles of a figure aren't deserialized
+    #[cfg_attr(feature = "serde", serde(skip))]
+    line_style: Option<LineStyleId>,
+    #[cfg_attr(feature = "serde", serde(deserialize_with = "crate::de::checked"))]
+    line_type: Option<LineType>,
+    #[cfg_attr(feature = "serde", serde(deserialize_with = "crate::de::positive"))]
     linewidth: Option<f64>,
 }
 
 impl Properties {
+    /// Select the axes to plot against
+    ///
+    /// **Note** By default, the `BottomXLeftY` axes are used
+    pub fn axes(&mut self, axes: Axes) -> &mut Properties {
+        self.axes = Some(axes);
+        self
+    }
+
+    /// Changes the end caps of the whiskers
+    ///
+    /// **Note** By default, the whiskers have no caps
+    ///
+    /// # Panics
+    ///
+    /// Panics if the size of `Caps::Size` is a negative value
+    pub fn caps(&mut self, caps: Caps) -> &mut Properties {
+        if let Caps::Size(size) = caps {
+            assert!(size >= 0.);
+        }
+
+        self.caps = caps;
+        self
+    }
+
     /// Sets the line color
     pub fn color(&mut self, color: Color) -> &mut Properties {
         self.color = Some(color);
@@ -26,17 +62,32 @@ impl Properties {
     /// Sets the legend label
     pub fn label<S>(&mut self, label: S) -> &mut Properties
     where
-        S: Into<Cow<'static, str>>,
+        S: Into<Text>,
     {
         self.label = Some(label.into());
         self
     }
 
+    /// References a line style defined with [`Figure::line_style`], the properties set on the
+    /// plot take precedence over the ones of the style
+    ///
+    /// [`Figure::line_style`]: ../struct.Figure.html#method.line_style
+    pub fn line_style(&mut self, style: LineStyleId) -> &mut Properties {
+        self.line_style = Some(style);
+        self
+    }
+
     /// Changes the line type
     ///
-    /// **Note** By default `Solid` lines are used
+    /// **Note** By default `Solid` lines are used, or the line type of the line style
+    ///
+    /// # Panics
+    ///
+    /// Panics if a `Custom` dash pattern is invalid
     pub fn line_type(&mut self, lt: LineType) -> &mut Properties {
-        self.line_type = lt;
+        lt.check();
+
+        self.line_type = Some(lt);
         self
     }
 
@@ -56,19 +107,51 @@ impl Properties {
 impl Default for Properties {
     fn default() -> Properties {
         Properties {
+            axes: None,
+            caps: Caps::None,
             color: None,
             label: None,
-            line_type: LineType::Solid,
+            line_style: None,
+            line_type: None,
             linewidth: None,
         }
     }
 }
 
 impl Script for Properties {
+    fn label(&self) -> Option<&Text> {
+        self.label.as_ref()
+    }
+
+    fn needs_color(&self) -> bool {
+        self.color.is_none() && self.line_style.is_none()
+    }
+
+    fn needs_line_width(&self) -> bool {
+        self.linewidth.is_none() && self.line_style.is_none()
+    }
+
     fn script(&self) -> String {
-        let mut script = String::from("with candlesticks ");
+        let mut script = if let Some(axes) = self.axes {
+            format!("axes {} ", axes.display())
+        } else {
+            String::new()
+        };
+
+        script.push_str("with candlesticks ");
+
+        match self.caps {
+            Caps::None => {}
+            caps => script.push_str(&format!("whiskerbars {} ", caps.size())),
+        }
+
+        if let Some(ls) = self.line_style {
+            script.push_str(&format!("ls {} ", ls.number()))
+        }
 
-        script.push_str(&format!("lt {} ", self.line_type.display()));
+        if let Some(ref lt) = self.line_type {
+            script.push_str(&format!("dt {} ", lt.display()))
+        }
 
         if let Some(lw) = self.linewidth {
             script.push_str(&format!("lw {} ", lw))
@@ -78,14 +161,6 @@ impl Script for Properties {
             script.push_str(&format!("lc rgb '{}' ", color.display()));
         }
 
-        if let Some(ref label) = self.label {
-            script.push_str("title '");
-            script.push_str(label);
-            script.push('\'')
-        } else {
-            script.push_str("notitle")
-        }
-
         script
     }
 }
@@ -127,7 +202,12 @@ where
     where
         F: FnOnce(&mut Properties) -> &mut Properties,
     {
-        let (x_factor, y_factor) = crate::scale_factor(&self.axes, crate::Axes::BottomXLeftY);
+        let mut props: Properties = Default::default();
+        configure(&mut props);
+
+        let axes = props.axes.unwrap_or(Axes::BottomXLeftY);
+        let (x_factor, y_factor) = crate::scale_factor(&self.axes, axes);
+
         let Candlesticks {
             x,
             whisker_min,
@@ -140,8 +220,89 @@ where
             izip!(x, box_min, whisker_min, whisker_high, box_high),
             (x_factor, y_factor, y_factor, y_factor, y_factor),
         );
-        self.plots
-            .push(Plot::new(data, configure(&mut Default::default())));
-        self
+        self.push(Plot::new(axes, &COLUMNS, data, &props), None)
+    }
+}
+
+/// Fast path for large owned or static series: the figure keeps the columns instead of a copy
+impl traits::Plot<Candlesticks<Column, Column, Column, Column, Column>> for Figure {
+    type Properties = Properties;
+
+    fn plot<F>(
+        &mut self,
+        candlesticks: Candlesticks<Column, Column, Column, Column, Column>,
+        configure: F,
+    ) -> &mut Figure
+    where
+        F: FnOnce(&mut Properties) -> &mut Properties,
+    {
+        let mut props: Properties = Default::default();
+        configure(&mut props);
+
+        let axes = props.axes.unwrap_or(Axes::BottomXLeftY);
+        let (x_factor, y_factor) = crate::scale_factor(&self.axes, axes);
+
+        let Candlesticks {
+            x: Column(x),
+            whisker_min: Column(whisker_min),
+            box_min: Column(box_min),
+            box_high: Column(box_high),
+            whisker_high: Column(whisker_high),
+        } = candlesticks;
+
+        let data = Matrix::streamed(vec![
+            (x, x_factor),
+            (box_min, y_factor),
+            (whisker_min, y_factor),
+            (whisker_high, y_factor),
+            (box_high, y_factor),
+        ]);
+        self.push(Plot::new(axes, &COLUMNS, data, &props), None)
+    }
+}
+
+/// Order in which gnuplot expects the columns of a candlestick
+const COLUMNS: [Coordinate; 5] = [
+    Coordinate::X,
+    Coordinate::Y,
+    Coordinate::Y,
+    Coordinate::Y,
+    Coordinate::Y,
+];
+
+#[cfg(test)]
+mod test {
+    use crate::prelude::*;
+
+    #[test]
+    fn columns_and_iterators_produce_the_same_plot() {
+        let (x, low, high) = (vec![1., 2.], vec![0., 1.], vec![3., 4.]);
+        let (box_min, box_high) = (vec![1., 2.], vec![2., 3.]);
+
+        let mut iterators = Figure::new();
+        iterators.plot(
+            Candlesticks {
+                x: &x,
+                whisker_min: &low,
+                box_min: &box_min,
+                box_high: &box_high,
+                whisker_high: &high,
+            },
+            |c| c.axes(Axes::BottomXRightY),
+        );
+        let mut columns = Figure::new();
+        columns.plot(
+            Candlesticks {
+                x: Column::from(x),
+                whisker_min: Column::from(low),
+                box_min: Column::from(box_min),
+                box_high: Column::from(box_high),
+                whisker_high: Column::from(high),
+            },
+            |c| c.axes(Axes::BottomXRightY),
+        );
+
+        assert_eq!(iterators.script(), columns.script());
+        assert_eq!(columns.data_range(Axis::RightY), Some((0., 4.)));
     }
 }
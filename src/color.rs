@@ -0,0 +1,510 @@
+//! Color construction, parsing and blending
+
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::{Color, Display};
+
+impl Color {
+    /// Creates a color from its hue (in degrees), saturation and lightness (both within
+    /// `[0, 1]`)
+    ///
+    /// # Panics
+    ///
+    /// Panics if `saturation` or `lightness` are outside the range `[0, 1]`
+    pub fn hsl(hue: f64, saturation: f64, lightness: f64) -> Color {
+        assert!((0. ..=1.).contains(&saturation));
+        assert!((0. ..=1.).contains(&lightness));
+
+        let (r, g, b) = hsl_to_rgb(hue, saturation, lightness);
+        Color::Rgb(r, g, b)
+    }
+
+    /// The same color with its opacity set to `opacity`, within `[0, 1]`
+    ///
+    /// # Panics
+    ///
+    /// Panics if `opacity` is outside the range `[0, 1]`
+    pub fn with_opacity(self, opacity: f64) -> Color {
+        assert!((0. ..=1.).contains(&opacity));
+
+        let (r, g, b, _) = self.rgba();
+        from_rgba(r, g, b, (opacity * 255.).round() as u8)
+    }
+
+    /// A lighter color, `amount` (within `[0, 1]`) is added to the lightness of the color
+    ///
+    /// # Panics
+    ///
+    /// Panics if `amount` is outside the range `[0, 1]`
+    pub fn lighten(self, amount: f64) -> Color {
+        assert!((0. ..=1.).contains(&amount));
+
+        self.map_lightness(|lightness| (lightness + amount).min(1.))
+    }
+
+    /// A darker color, `amount` (within `[0, 1]`) is subtracted from the lightness of the color
+    ///
+    /// # Panics
+    ///
+    /// Panics if `amount` is outside the range `[0, 1]`
+    pub fn darken(self, amount: f64) -> Color {
+        assert!((0. ..=1.).contains(&amount));
+
+        self.map_lightness(|lightness| (lightness - amount).max(0.))
+    }
+
+    /// Blends the color with `other`, `weight` (within `[0, 1]`) is the proportion of `other`
+    ///
+    /// # Panics
+    ///
+    /// Panics if `weight` is outside the range `[0, 1]`
+    pub fn mix(self, other: Color, weight: f64) -> Color {
+        assert!((0. ..=1.).contains(&weight));
+
+        let (r0, g0, b0, a0) = self.rgba();
+        let (r1, g1, b1, a1) = other.rgba();
+        let mix = |low: u8, high: u8| {
+            (f64::from(low) + weight * (f64::from(high) - f64::from(low))).round() as u8
+        };
+
+        from_rgba(mix(r0, r1), mix(g0, g1), mix(b0, b1), mix(a0, a1))
+    }
+
+    /// The red, green, blue and alpha components of the color
+    pub(crate) fn rgba(self) -> (u8, u8, u8, u8) {
+        match self {
+            Color::Rgba(r, g, b, a) => (r, g, b, a),
+            color => {
+                let (r, g, b) = color.rgb();
+                (r, g, b, 0xff)
+            }
+        }
+    }
+
+    fn map_lightness<F>(self, f: F) -> Color
+    where
+        F: FnOnce(f64) -> f64,
+    {
+        let (r, g, b, a) = self.rgba();
+        let (hue, saturation, lightness) = rgb_to_hsl(r, g, b);
+        let (r, g, b) = hsl_to_rgb(hue, saturation, f(lightness));
+
+        from_rgba(r, g, b, a)
+    }
+}
+
+/// An `Rgb` color if `a` is opaque, `Rgba` otherwise
+fn from_rgba(r: u8, g: u8, b: u8, a: u8) -> Color {
+    if a == 0xff {
+        Color::Rgb(r, g, b)
+    } else {
+        Color::Rgba(r, g, b, a)
+    }
+}
+
+fn hsl_to_rgb(hue: f64, saturation: f64, lightness: f64) -> (u8, u8, u8) {
+    let chroma = (1. - (2. * lightness - 1.).abs()) * saturation;
+    let hue = hue.rem_euclid(360.) / 60.;
+    let x = chroma * (1. - (hue % 2. - 1.).abs());
+
+    let (r, g, b) = match hue as u8 {
+        0 => (chroma, x, 0.),
+        1 => (x, chroma, 0.),
+        2 => (0., chroma, x),
+        3 => (0., x, chroma),
+        4 => (x, 0., chroma),
+        _ => (chroma, 0., x),
+    };
+    let m = lightness - chroma / 2.;
+    let channel = |c: f64| ((c + m) * 255.).round() as u8;
+
+    (channel(r), channel(g), channel(b))
+}
+
+fn rgb_to_hsl(r: u8, g: u8, b: u8) -> (f64, f64, f64) {
+    let (r, g, b) = (
+        f64::from(r) / 255.,
+        f64::from(g) / 255.,
+        f64::from(b) / 255.,
+    );
+    let max = r.max(g).max(b);
+    let min = r.min(g).min(b);
+    let chroma = max - min;
+    let lightness = (max + min) / 2.;
+
+    if chroma == 0. {
+        return (0., 0., lightness);
+    }
+
+    let hue = if max == r {
+        60. * ((g - b) / chroma).rem_euclid(6.)
+    } else if max == g {
+        60. * ((b - r) / chroma + 2.)
+    } else {
+        60. * ((r - g) / chroma + 4.)
+    };
+    let saturation = chroma / (1. - (2. * lightness - 1.).abs());
+
+    (hue, saturation, lightness)
+}
+
+/// Error returned when a string is not a valid color
+#[derive(Debug)]
+pub struct ParseColorError {
+    input: String,
+}
+
+impl fmt::Display for ParseColorError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "`{}` is not a color, expected `#rrggbb`, `#aarrggbb` or a color name",
+            self.input
+        )
+    }
+}
+
+impl Error for ParseColorError {}
+
+/// Parses `#rrggbb`, gnuplot's `#aarrggbb`, where `aa` is the transparency (`00` is opaque), or
+/// a color name
+///
+/// Names are matched case insensitively against the color names of gnuplot, as listed by `show
+/// colornames` (e.g. `dark-violet`, `web-green`, `gray40`), then against the CSS color names (e.g.
+/// `tomato`, `rebeccapurple`). gnuplot's value wins for the names that both define (e.g. `purple`).
+impl FromStr for Color {
+    type Err = ParseColorError;
+
+    fn from_str(s: &str) -> Result<Color, ParseColorError> {
+        let error = || ParseColorError {
+            input: s.to_owned(),
+        };
+        let s = s.trim();
+
+        if let Some(hex) = s.strip_prefix('#') {
+            let byte = |i: usize| {
+                hex.get(i..i + 2)
+                    .and_then(|byte| u8::from_str_radix(byte, 16).ok())
+                    .ok_or_else(error)
+            };
+
+            return match hex.len() {
+                6 => Ok(Color::Rgb(byte(0)?, byte(2)?, byte(4)?)),
+                8 => Ok(from_rgba(byte(2)?, byte(4)?, byte(6)?, 0xff - byte(0)?)),
+                _ => Err(error()),
+            };
+        }
+
+        let name = s.to_ascii_lowercase();
+        let &(_, (r, g, b)) = NAMES
+            .iter()
+            .chain(CSS_NAMES)
+            .find(|&&(n, _)| n == name)
+            .ok_or_else(error)?;
+
+        // The names that have a `Color` variant keep it
+        let color = VARIANTS
+            .iter()
+            .cloned()
+            .find(|variant| variant.display() == name)
+            .unwrap_or(Color::Rgb(r, g, b));
+
+        Ok(color)
+    }
+}
+
+/// gnuplot's color names and their values, in the order of `show colornames`
+const NAMES: &[(&str, (u8, u8, u8))] = &[
+    ("white", (0xff, 0xff, 0xff)),
+    ("black", (0x00, 0x00, 0x00)),
+    ("dark-grey", (0xa0, 0xa0, 0xa0)),
+    ("red", (0xff, 0x00, 0x00)),
+    ("web-green", (0x00, 0xc0, 0x00)),
+    ("web-blue", (0x00, 0x80, 0xff)),
+    ("dark-magenta", (0xc0, 0x00, 0xff)),
+    ("dark-cyan", (0x00, 0xee, 0xee)),
+    ("dark-orange", (0xc0, 0x40, 0x00)),
+    ("dark-yellow", (0xc8, 0xc8, 0x00)),
+    ("royalblue", (0x41, 0x69, 0xe1)),
+    ("goldenrod", (0xff, 0xc0, 0x20)),
+    ("dark-spring-green", (0x00, 0x80, 0x40)),
+    ("purple", (0xc0, 0x80, 0xff)),
+    ("steelblue", (0x30, 0x60, 0x80)),
+    ("dark-red", (0x8b, 0x00, 0x00)),
+    ("dark-chartreuse", (0x40, 0x80, 0x00)),
+    ("orchid", (0xff, 0x80, 0xff)),
+    ("aquamarine", (0x7f, 0xff, 0xd4)),
+    ("brown", (0xa5, 0x2a, 0x2a)),
+    ("yellow", (0xff, 0xff, 0x00)),
+    ("turquoise", (0x40, 0xe0, 0xd0)),
+    ("grey0", (0x00, 0x00, 0x00)),
+    ("grey10", (0x1a, 0x1a, 0x1a)),
+    ("grey20", (0x33, 0x33, 0x33)),
+    ("grey30", (0x4d, 0x4d, 0x4d)),
+    ("grey40", (0x66, 0x66, 0x66)),
+    ("grey50", (0x7f, 0x7f, 0x7f)),
+    ("grey60", (0x99, 0x99, 0x99)),
+    ("grey70", (0xb3, 0xb3, 0xb3)),
+    ("grey", (0xc0, 0xc0, 0xc0)),
+    ("grey80", (0xcc, 0xcc, 0xcc)),
+    ("grey90", (0xe5, 0xe5, 0xe5)),
+    ("grey100", (0xff, 0xff, 0xff)),
+    ("light-red", (0xf0, 0x32, 0x32)),
+    ("light-green", (0x90, 0xee, 0x90)),
+    ("light-blue", (0xad, 0xd8, 0xe6)),
+    ("light-magenta", (0xf0, 0x55, 0xf0)),
+    ("light-cyan", (0xe0, 0xff, 0xff)),
+    ("light-goldenrod", (0xee, 0xdd, 0x82)),
+    ("light-pink", (0xff, 0xb6, 0xc1)),
+    ("light-turquoise", (0xaf, 0xee, 0xee)),
+    ("gold", (0xff, 0xd7, 0x00)),
+    ("green", (0x00, 0xff, 0x00)),
+    ("dark-green", (0x00, 0x64, 0x00)),
+    ("spring-green", (0x00, 0xff, 0x7f)),
+    ("forest-green", (0x22, 0x8b, 0x22)),
+    ("sea-green", (0x2e, 0x8b, 0x57)),
+    ("blue", (0x00, 0x00, 0xff)),
+    ("dark-blue", (0x00, 0x00, 0x8b)),
+    ("midnight-blue", (0x19, 0x19, 0x70)),
+    ("navy", (0x00, 0x00, 0x80)),
+    ("medium-blue", (0x00, 0x00, 0xcd)),
+    ("skyblue", (0x87, 0xce, 0xeb)),
+    ("cyan", (0x00, 0xff, 0xff)),
+    ("magenta", (0xff, 0x00, 0xff)),
+    ("dark-turquoise", (0x00, 0xce, 0xd1)),
+    ("dark-pink", (0xff, 0x14, 0x93)),
+    ("coral", (0xff, 0x7f, 0x50)),
+    ("light-coral", (0xf0, 0x80, 0x80)),
+    ("orange-red", (0xff, 0x45, 0x00)),
+    ("salmon", (0xfa, 0x80, 0x72)),
+    ("dark-salmon", (0xe9, 0x96, 0x7a)),
+    ("khaki", (0xf0, 0xe6, 0x8c)),
+    ("dark-khaki", (0xbd, 0xb7, 0x6b)),
+    ("dark-goldenrod", (0xb8, 0x86, 0x0b)),
+    ("beige", (0xf5, 0xf5, 0xdc)),
+    ("olive", (0xa0, 0x80, 0x20)),
+    ("orange", (0xff, 0xa5, 0x00)),
+    ("violet", (0xee, 0x82, 0xee)),
+    ("dark-violet", (0x94, 0x00, 0xd3)),
+    ("plum", (0xdd, 0xa0, 0xdd)),
+    ("dark-plum", (0x90, 0x50, 0x40)),
+    ("dark-olivegreen", (0x55, 0x6b, 0x2f)),
+    ("orangered4", (0x80, 0x14, 0x00)),
+    ("brown4", (0x80, 0x14, 0x14)),
+    ("sienna4", (0x80, 0x40, 0x14)),
+    ("orchid4", (0x80, 0x40, 0x80)),
+    ("mediumpurple3", (0x80, 0x60, 0xc0)),
+    ("slateblue1", (0x80, 0x60, 0xff)),
+    ("yellow4", (0x80, 0x80, 0x00)),
+    ("sienna1", (0xff, 0x80, 0x40)),
+    ("tan1", (0xff, 0xa0, 0x40)),
+    ("sandybrown", (0xff, 0xa0, 0x60)),
+    ("light-salmon", (0xff, 0xa0, 0x70)),
+    ("pink", (0xff, 0xc0, 0xc0)),
+    ("khaki1", (0xff, 0xff, 0x80)),
+    ("lemonchiffon", (0xff, 0xff, 0xc0)),
+    ("bisque", (0xcd, 0xb7, 0x9e)),
+    ("honeydew", (0xf0, 0xff, 0xf0)),
+    ("slategrey", (0xa0, 0xb6, 0xcd)),
+    ("seagreen", (0xc1, 0xff, 0xc1)),
+    ("antiquewhite", (0xcd, 0xc0, 0xb0)),
+    ("chartreuse", (0x7c, 0xff, 0x40)),
+    ("greenyellow", (0xa0, 0xff, 0x20)),
+    ("gray", (0xbe, 0xbe, 0xbe)),
+    ("light-gray", (0xd3, 0xd3, 0xd3)),
+    ("light-grey", (0xd3, 0xd3, 0xd3)),
+    ("dark-gray", (0xa0, 0xa0, 0xa0)),
+    ("slategray", (0xa0, 0xb6, 0xcd)),
+    ("gray0", (0x00, 0x00, 0x00)),
+    ("gray10", (0x1a, 0x1a, 0x1a)),
+    ("gray20", (0x33, 0x33, 0x33)),
+    ("gray30", (0x4d, 0x4d, 0x4d)),
+    ("gray40", (0x66, 0x66, 0x66)),
+    ("gray50", (0x7f, 0x7f, 0x7f)),
+    ("gray60", (0x99, 0x99, 0x99)),
+    ("gray70", (0xb3, 0xb3, 0xb3)),
+    ("gray80", (0xcc, 0xcc, 0xcc)),
+    ("gray90", (0xe5, 0xe5, 0xe5)),
+    ("gray100", (0xff, 0xff, 0xff)),
+];
+
+/// CSS color names, without the ones that gnuplot also defines, whose gnuplot value is used
+const CSS_NAMES: &[(&str, (u8, u8, u8))] = &[
+    ("aliceblue", (0xf0, 0xf8, 0xff)),
+    ("aqua", (0x00, 0xff, 0xff)),
+    ("azure", (0xf0, 0xff, 0xff)),
+    ("blanchedalmond", (0xff, 0xeb, 0xcd)),
+    ("blueviolet", (0x8a, 0x2b, 0xe2)),
+    ("burlywood", (0xde, 0xb8, 0x87)),
+    ("cadetblue", (0x5f, 0x9e, 0xa0)),
+    ("chocolate", (0xd2, 0x69, 0x1e)),
+    ("cornflowerblue", (0x64, 0x95, 0xed)),
+    ("cornsilk", (0xff, 0xf8, 0xdc)),
+    ("crimson", (0xdc, 0x14, 0x3c)),
+    ("darkblue", (0x00, 0x00, 0x8b)),
+    ("darkcyan", (0x00, 0x8b, 0x8b)),
+    ("darkgoldenrod", (0xb8, 0x86, 0x0b)),
+    ("darkgray", (0xa9, 0xa9, 0xa9)),
+    ("darkgreen", (0x00, 0x64, 0x00)),
+    ("darkgrey", (0xa9, 0xa9, 0xa9)),
+    ("darkkhaki", (0xbd, 0xb7, 0x6b)),
+    ("darkmagenta", (0x8b, 0x00, 0x8b)),
+    ("darkolivegreen", (0x55, 0x6b, 0x2f)),
+    ("darkorange", (0xff, 0x8c, 0x00)),
+    ("darkorchid", (0x99, 0x32, 0xcc)),
+    ("darkred", (0x8b, 0x00, 0x00)),
+    ("darksalmon", (0xe9, 0x96, 0x7a)),
+    ("darkseagreen", (0x8f, 0xbc, 0x8f)),
+    ("darkslateblue", (0x48, 0x3d, 0x8b)),
+    ("darkslategray", (0x2f, 0x4f, 0x4f)),
+    ("darkslategrey", (0x2f, 0x4f, 0x4f)),
+    ("darkturquoise", (0x00, 0xce, 0xd1)),
+    ("darkviolet", (0x94, 0x00, 0xd3)),
+    ("deeppink", (0xff, 0x14, 0x93)),
+    ("deepskyblue", (0x00, 0xbf, 0xff)),
+    ("dimgray", (0x69, 0x69, 0x69)),
+    ("dimgrey", (0x69, 0x69, 0x69)),
+    ("dodgerblue", (0x1e, 0x90, 0xff)),
+    ("firebrick", (0xb2, 0x22, 0x22)),
+    ("floralwhite", (0xff, 0xfa, 0xf0)),
+    ("forestgreen", (0x22, 0x8b, 0x22)),
+    ("fuchsia", (0xff, 0x00, 0xff)),
+    ("gainsboro", (0xdc, 0xdc, 0xdc)),
+    ("ghostwhite", (0xf8, 0xf8, 0xff)),
+    ("hotpink", (0xff, 0x69, 0xb4)),
+    ("indianred", (0xcd, 0x5c, 0x5c)),
+    ("indigo", (0x4b, 0x00, 0x82)),
+    ("ivory", (0xff, 0xff, 0xf0)),
+    ("lavender", (0xe6, 0xe6, 0xfa)),
+    ("lavenderblush", (0xff, 0xf0, 0xf5)),
+    ("lawngreen", (0x7c, 0xfc, 0x00)),
+    ("lightblue", (0xad, 0xd8, 0xe6)),
+    ("lightcoral", (0xf0, 0x80, 0x80)),
+    ("lightcyan", (0xe0, 0xff, 0xff)),
+    ("lightgoldenrodyellow", (0xfa, 0xfa, 0xd2)),
+    ("lightgray", (0xd3, 0xd3, 0xd3)),
+    ("lightgreen", (0x90, 0xee, 0x90)),
+    ("lightgrey", (0xd3, 0xd3, 0xd3)),
+    ("lightpink", (0xff, 0xb6, 0xc1)),
+    ("lightsalmon", (0xff, 0xa0, 0x7a)),
+    ("lightseagreen", (0x20, 0xb2, 0xaa)),
+    ("lightskyblue", (0x87, 0xce, 0xfa)),
+    ("lightslategray", (0x77, 0x88, 0x99)),
+    ("lightslategrey", (0x77, 0x88, 0x99)),
+    ("lightsteelblue", (0xb0, 0xc4, 0xde)),
+    ("lightyellow", (0xff, 0xff, 0xe0)),
+    ("lime", (0x00, 0xff, 0x00)),
+    ("limegreen", (0x32, 0xcd, 0x32)),
+    ("linen", (0xfa, 0xf0, 0xe6)),
+    ("maroon", (0x80, 0x00, 0x00)),
+    ("mediumaquamarine", (0x66, 0xcd, 0xaa)),
+    ("mediumblue", (0x00, 0x00, 0xcd)),
+    ("mediumorchid", (0xba, 0x55, 0xd3)),
+    ("mediumpurple", (0x93, 0x70, 0xdb)),
+    ("mediumseagreen", (0x3c, 0xb3, 0x71)),
+    ("mediumslateblue", (0x7b, 0x68, 0xee)),
+    ("mediumspringgreen", (0x00, 0xfa, 0x9a)),
+    ("mediumturquoise", (0x48, 0xd1, 0xcc)),
+    ("mediumvioletred", (0xc7, 0x15, 0x85)),
+    ("midnightblue", (0x19, 0x19, 0x70)),
+    ("mintcream", (0xf5, 0xff, 0xfa)),
+    ("mistyrose", (0xff, 0xe4, 0xe1)),
+    ("moccasin", (0xff, 0xe4, 0xb5)),
+    ("navajowhite", (0xff, 0xde, 0xad)),
+    ("oldlace", (0xfd, 0xf5, 0xe6)),
+    ("olivedrab", (0x6b, 0x8e, 0x23)),
+    ("orangered", (0xff, 0x45, 0x00)),
+    ("palegoldenrod", (0xee, 0xe8, 0xaa)),
+    ("palegreen", (0x98, 0xfb, 0x98)),
+    ("paleturquoise", (0xaf, 0xee, 0xee)),
+    ("palevioletred", (0xdb, 0x70, 0x93)),
+    ("papayawhip", (0xff, 0xef, 0xd5)),
+    ("peachpuff", (0xff, 0xda, 0xb9)),
+    ("peru", (0xcd, 0x85, 0x3f)),
+    ("powderblue", (0xb0, 0xe0, 0xe6)),
+    ("rebeccapurple", (0x66, 0x33, 0x99)),
+    ("rosybrown", (0xbc, 0x8f, 0x8f)),
+    ("saddlebrown", (0x8b, 0x45, 0x13)),
+    ("seashell", (0xff, 0xf5, 0xee)),
+    ("sienna", (0xa0, 0x52, 0x2d)),
+    ("silver", (0xc0, 0xc0, 0xc0)),
+    ("slateblue", (0x6a, 0x5a, 0xcd)),
+    ("snow", (0xff, 0xfa, 0xfa)),
+    ("springgreen", (0x00, 0xff, 0x7f)),
+    ("tan", (0xd2, 0xb4, 0x8c)),
+    ("teal", (0x00, 0x80, 0x80)),
+    ("thistle", (0xd8, 0xbf, 0xd8)),
+    ("tomato", (0xff, 0x63, 0x47)),
+    ("wheat", (0xf5, 0xde, 0xb3)),
+    ("whitesmoke", (0xf5, 0xf5, 0xf5)),
+    ("yellowgreen", (0x9a, 0xcd, 0x32)),
+];
+
+/// The colors that are displayed with their gnuplot name
+const VARIANTS: [Color; 12] = [
+    Color::Black,
+    Color::Blue,
+    Color::Cyan,
+    Color::DarkViolet,
+    Color::ForestGreen,
+    Color::Gold,
+    Color::Gray,
+    Color::Green,
+    Color::Magenta,
+    Color::Red,
+    Color::White,
+    Color::Yellow,
+];
+
+#[cfg(test)]
+mod test {
+    use crate::{Color, Display};
+
+    fn hex(color: Color) -> String {
+        color.display().into_owned()
+    }
+
+    #[test]
+    fn parse() {
+        assert_eq!("#ff6347", hex("#FF6347".parse().unwrap()));
+        assert_eq!("#80ff6347", hex("#80ff6347".parse().unwrap()));
+        assert_eq!("#ff6347", hex("#00ff6347".parse().unwrap()));
+        assert_eq!("#ff4500", hex("Orange-Red".parse().unwrap()));
+        assert_eq!("dark-violet", hex("dark-violet".parse().unwrap()));
+        assert_eq!("gray", hex("gray".parse().unwrap()));
+        assert_eq!("#c0c0c0", hex("grey".parse().unwrap()));
+        assert_eq!("#00c000", hex("web-green".parse().unwrap()));
+        assert_eq!("#c000ff", hex("dark-magenta".parse().unwrap()));
+        assert_eq!("#666666", hex("gray40".parse().unwrap()));
+        assert_eq!("#8b008b", hex("DarkMagenta".parse().unwrap()));
+        assert_eq!("#ff6347", hex("tomato".parse().unwrap()));
+        assert_eq!("#663399", hex("rebeccapurple".parse().unwrap()));
+        assert_eq!("#c080ff", hex("purple".parse().unwrap()));
+        assert!("dark_magenta".parse::<Color>().is_err());
+        assert!("#12345".parse::<Color>().is_err());
+        assert!("#gg0000".parse::<Color>().is_err());
+        assert!("blurple".parse::<Color>().is_err());
+    }
+
+    #[test]
+    fn hsl() {
+        assert_eq!("#ff0000", hex(Color::hsl(0., 1., 0.5)));
+        assert_eq!("#00ff00", hex(Color::hsl(120., 1., 0.5)));
+        assert_eq!("#0000ff", hex(Color::hsl(-120., 1., 0.5)));
+        assert_eq!("#808080", hex(Color::hsl(42., 0., 0.5)));
+    }
+
+    #[test]
+    fn blending() {
+        assert_eq!("#ff8080", hex(Color::Red.lighten(0.25)));
+        assert_eq!("#800000", hex(Color::Red.darken(0.25)));
+        assert_eq!("#800080", hex(Color::Red.mix(Color::Blue, 0.5)));
+        assert_eq!("#7fff0000", hex(Color::Red.with_opacity(0.5)));
+        assert_eq!(
+            "#3f800080",
+            hex(Color::Red.with_opacity(0.5).mix(Color::Blue, 0.5))
+        );
+    }
+}
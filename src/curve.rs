@@ -5,22 +5,53 @@ use std::borrow::Cow;
 use std::iter::IntoIterator;
 
 use crate::data::Matrix;
+use crate::downsample::select;
 use crate::traits::{self, Data};
-use crate::{Axes, Color, CurveDefault, Display, Figure, LineType, Plot, PointType, Script};
+use crate::{
+    Axes, Color, Column, Coordinate, CurveDefault, Display, Downsampling, Figure, LineStyleId,
+    LineType, MissingValues, Plot, PointType, Script, Text,
+};
 
 /// Properties common to simple "curve" like plots
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(feature = "serde", serde(default = "Properties::lines"))]
 pub struct Properties {
     axes: Option<Axes>,
     color: Option<Color>,
-    label: Option<Cow<'static, str>>,
-    line_type: LineType,
+    downsampling: Option<Downsampling>,
+    label: Option<Text>,
+    // The line styles of a figure aren't deserialized
+    #[cfg_attr(feature = "serde", serde(skip))]
+    line_style: Option<LineStyleId>,
+    #[cfg_attr(feature = "serde", serde(deserialize_with = "crate::de::checked"))]
+    line_type: Option<LineType>,
+    #[cfg_attr(feature = "serde", serde(deserialize_with = "crate::de::positive"))]
     linewidth: Option<f64>,
+    missing_values: Option<MissingValues>,
     point_type: Option<PointType>,
+    #[cfg_attr(feature = "serde", serde(deserialize_with = "crate::de::positive"))]
     point_size: Option<f64>,
+    smooth: Option<Smooth>,
+    #[cfg_attr(feature = "serde", serde(skip))]
     style: Style,
 }
 
 impl Properties {
+    #[cfg(feature = "serde")]
+    fn lines() -> Properties {
+        CurveDefault::default(Style::Lines)
+    }
+
+    /// Replaces every property but the style, which is picked by the plot
+    #[cfg(feature = "serde")]
+    pub(crate) fn replace(&mut self, properties: Properties) -> &mut Properties {
+        *self = Properties {
+            style: self.style,
+            ..properties
+        };
+        self
+    }
+
     /// Select the axes to plot against
     ///
     /// **Note** By default, the `BottomXLeftY` axes are used
@@ -35,20 +66,46 @@ impl Properties {
         self
     }
 
+    /// Changes how the series is downsampled before being plotted
+    ///
+    /// **Note** By default, the downsampling configured on the figure is used, its target width
+    /// is the one of the figure when the curve is plotted
+    ///
+    /// **Note** Smoothed curves are never downsampled
+    pub fn downsampling(&mut self, downsampling: Downsampling) -> &mut Properties {
+        self.downsampling = Some(downsampling);
+        self
+    }
+
     /// Sets the legend label
     pub fn label<S>(&mut self, label: S) -> &mut Properties
     where
-        S: Into<Cow<'static, str>>,
+        S: Into<Text>,
     {
         self.label = Some(label.into());
         self
     }
 
+    /// References a line style defined with [`Figure::line_style`], the properties set on the
+    /// plot take precedence over the ones of the style
+    ///
+    /// [`Figure::line_style`]: ../struct.Figure.html#method.line_style
+    pub fn line_style(&mut self, style: LineStyleId) -> &mut Properties {
+        self.line_style = Some(style);
+        self
+    }
+
     /// Changes the line type
     ///
-    /// **Note** By default `Solid` lines are used
+    /// **Note** By default `Solid` lines are used, or the line type of the line style
+    ///
+    /// # Panics
+    ///
+    /// Panics if a `Custom` dash pattern is invalid
     pub fn line_type(&mut self, lt: LineType) -> &mut Properties {
-        self.line_type = lt;
+        lt.check();
+
+        self.line_type = Some(lt);
         self
     }
 
@@ -64,6 +121,17 @@ impl Properties {
         self
     }
 
+    /// Changes how the missing (`NaN`, infinite or `None`) values of the series are handled
+    ///
+    /// **Note** By default, the policy configured on the figure is used
+    ///
+    /// **Note** With `MissingValues::Error`, a series that contains missing values makes drawing,
+    /// dumping or saving the figure fail
+    pub fn missing_values(&mut self, policy: MissingValues) -> &mut Properties {
+        self.missing_values = Some(policy);
+        self
+    }
+
     /// Changes the size of the points
     ///
     /// # Panics
@@ -81,6 +149,17 @@ impl Properties {
         self.point_type = Some(pt);
         self
     }
+
+    /// Smooths the series, or replaces it by one of its statistics, before plotting it
+    ///
+    /// **Note** By default, the data points are plotted as is
+    ///
+    /// **Note** The padded ranges and the formatted tics of the axes are computed from the data
+    /// points, not from the statistics of `Cnormal`, `Cumulative` and `Frequency`
+    pub fn smooth(&mut self, smooth: Smooth) -> &mut Properties {
+        self.smooth = Some(smooth);
+        self
+    }
 }
 
 impl CurveDefault<Style> for Properties {
@@ -88,26 +167,53 @@ impl CurveDefault<Style> for Properties {
         Properties {
             axes: None,
             color: None,
+            downsampling: None,
             label: None,
-            line_type: LineType::Solid,
+            line_style: None,
+            line_type: None,
             linewidth: None,
+            missing_values: None,
             point_size: None,
             point_type: None,
+            smooth: None,
             style,
         }
     }
 }
 
 impl Script for Properties {
+    fn label(&self) -> Option<&Text> {
+        self.label.as_ref()
+    }
+
+    fn needs_color(&self) -> bool {
+        self.color.is_none() && self.line_style.is_none()
+    }
+
+    fn needs_line_width(&self) -> bool {
+        self.linewidth.is_none() && self.line_style.is_none()
+    }
+
     fn script(&self) -> String {
-        let mut script = if let Some(axes) = self.axes {
-            format!("axes {} ", axes.display())
+        let mut script = if let Some(smooth) = self.smooth {
+            format!("smooth {} ", smooth.display())
         } else {
             String::new()
         };
 
+        if let Some(axes) = self.axes {
+            script.push_str(&format!("axes {} ", axes.display()))
+        }
+
         script.push_str(&format!("with {} ", self.style.display()));
-        script.push_str(&format!("lt {} ", self.line_type.display()));
+
+        if let Some(ls) = self.line_style {
+            script.push_str(&format!("ls {} ", ls.number()))
+        }
+
+        if let Some(ref lt) = self.line_type {
+            script.push_str(&format!("dt {} ", lt.display()))
+        }
 
         if let Some(lw) = self.linewidth {
             script.push_str(&format!("lw {} ", lw))
@@ -125,18 +231,32 @@ impl Script for Properties {
             script.push_str(&format!("ps {} ", ps))
         }
 
-        if let Some(ref label) = self.label {
-            script.push_str("title '");
-            script.push_str(label);
-            script.push('\'')
-        } else {
-            script.push_str("notitle")
-        }
-
         script
     }
 }
 
+/// How the data of a curve is smoothed, matches the `smooth` options of gnuplot
+#[derive(Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub enum Smooth {
+    /// Weighted cubic splines that approximate the data points
+    Acsplines,
+    /// Bezier curve of degree `n` through the `n` data points
+    Bezier,
+    /// Cumulative distribution, normalized to `[0, 1]` (e.g. an ECDF of the `x` values)
+    Cnormal,
+    /// Natural cubic splines through the data points, after making `x` monotonic
+    Csplines,
+    /// Running sum of `y`, after making `x` monotonic
+    Cumulative,
+    /// Sum of the `y` values of each distinct `x` (e.g. a histogram)
+    Frequency,
+    /// Kernel density estimate of the distribution of the `x` values, weighted by `y`
+    Kdensity,
+    /// Average of the `y` values of each distinct `x`, after making `x` monotonic
+    Unique,
+}
+
 /// Types of "curve" plots
 pub enum Curve<X, Y> {
     /// A minimally sized dot on each data point
@@ -146,6 +266,27 @@ pub enum Curve<X, Y> {
         /// Y coordinate of the data points
         y: Y,
     },
+    /// Steps `|‾` that go up (or down) at each data point, then across to the next one
+    FSteps {
+        /// X coordinate of the data points
+        x: X,
+        /// Y coordinate of the data points
+        y: Y,
+    },
+    /// Steps `_|` like `Steps`, with the area under them filled
+    FillSteps {
+        /// X coordinate of the data points
+        x: X,
+        /// Y coordinate of the data points
+        y: Y,
+    },
+    /// Steps centered on each data point, like the bars of a histogram
+    HiSteps {
+        /// X coordinate of the data points
+        x: X,
+        /// Y coordinate of the data points
+        y: Y,
+    },
     /// A vertical "impulse" on each data point
     Impulses {
         /// X coordinate of the data points
@@ -187,6 +328,9 @@ impl<X, Y> Curve<X, Y> {
     fn style(&self) -> Style {
         match *self {
             Curve::Dots { .. } => Style::Dots,
+            Curve::FSteps { .. } => Style::FSteps,
+            Curve::FillSteps { .. } => Style::FillSteps,
+            Curve::HiSteps { .. } => Style::HiSteps,
             Curve::Impulses { .. } => Style::Impulses,
             Curve::Lines { .. } => Style::Lines,
             Curve::LinesPoints { .. } => Style::LinesPoints,
@@ -194,11 +338,28 @@ impl<X, Y> Curve<X, Y> {
             Curve::Steps { .. } => Style::Steps,
         }
     }
+
+    fn into_data(self) -> (X, Y) {
+        match self {
+            Curve::Dots { x, y }
+            | Curve::FSteps { x, y }
+            | Curve::FillSteps { x, y }
+            | Curve::HiSteps { x, y }
+            | Curve::Impulses { x, y }
+            | Curve::Lines { x, y }
+            | Curve::LinesPoints { x, y }
+            | Curve::Points { x, y }
+            | Curve::Steps { x, y } => (x, y),
+        }
+    }
 }
 
 #[derive(Clone, Copy)]
 enum Style {
     Dots,
+    FSteps,
+    FillSteps,
+    HiSteps,
     Impulses,
     Lines,
     LinesPoints,
@@ -210,6 +371,9 @@ impl Display<&'static str> for Style {
     fn display(&self) -> &'static str {
         match *self {
             Style::Dots => "dots",
+            Style::FSteps => "fsteps",
+            Style::FillSteps => "fillsteps",
+            Style::HiSteps => "histeps",
             Style::Impulses => "impulses",
             Style::Lines => "lines",
             Style::LinesPoints => "linespoints",
@@ -233,23 +397,152 @@ where
         F: FnOnce(&mut Properties) -> &mut Properties,
     {
         let style = curve.style();
-        let (x, y) = match curve {
-            Curve::Dots { x, y }
-            | Curve::Impulses { x, y }
-            | Curve::Lines { x, y }
-            | Curve::LinesPoints { x, y }
-            | Curve::Points { x, y }
-            | Curve::Steps { x, y } => (x, y),
+        let (x, y) = curve.into_data();
+
+        let mut props = CurveDefault::default(style);
+        configure(&mut props);
+
+        let axes = props.axes.unwrap_or(crate::Axes::BottomXLeftY);
+        let (x_factor, y_factor) = crate::scale_factor(&self.axes, axes);
+
+        // The statistics computed by gnuplot need every data point
+        let downsampling = if props.smooth.is_some() {
+            Downsampling::None
+        } else {
+            props.downsampling.unwrap_or(self.downsampling)
+        };
+        let data = if let Downsampling::None = downsampling {
+            Matrix::new(izip!(x, y), (x_factor, y_factor))
+        } else {
+            let (x, y): (Vec<_>, Vec<_>) = izip!(x, y).map(|(x, y)| (x.f64(), y.f64())).unzip();
+
+            match downsampling.indices(&x, &y, self.width()) {
+                Some(kept) => Matrix::from_columns(
+                    &[&select(&x, &kept), &select(&y, &kept)],
+                    &[x_factor, y_factor],
+                ),
+                None => Matrix::from_columns(&[&x, &y], &[x_factor, y_factor]),
+            }
         };
 
+        let missing_values = props.missing_values;
+        self.push(
+            Plot::new(axes, &[Coordinate::X, Coordinate::Y], data, &props),
+            missing_values,
+        )
+    }
+}
+
+/// Fast path for large owned or static series: the figure keeps the columns instead of a copy
+impl traits::Plot<Curve<Column, Column>> for Figure {
+    type Properties = Properties;
+
+    fn plot<F>(&mut self, curve: Curve<Column, Column>, configure: F) -> &mut Figure
+    where
+        F: FnOnce(&mut Properties) -> &mut Properties,
+    {
+        let style = curve.style();
+        let (Column(mut x), Column(mut y)) = curve.into_data();
+
         let mut props = CurveDefault::default(style);
         configure(&mut props);
 
-        let (x_factor, y_factor) =
-            crate::scale_factor(&self.axes, props.axes.unwrap_or(crate::Axes::BottomXLeftY));
+        let axes = props.axes.unwrap_or(crate::Axes::BottomXLeftY);
+        let (x_factor, y_factor) = crate::scale_factor(&self.axes, axes);
 
-        let data = Matrix::new(izip!(x, y), (x_factor, y_factor));
-        self.plots.push(Plot::new(data, &props));
-        self
+        // The statistics computed by gnuplot need every data point
+        let downsampling = if props.smooth.is_some() {
+            Downsampling::None
+        } else {
+            props.downsampling.unwrap_or(self.downsampling)
+        };
+        if let Some(kept) = downsampling.indices(&x, &y, self.width()) {
+            x = Cow::Owned(select(&x, &kept));
+            y = Cow::Owned(select(&y, &kept));
+        }
+
+        let data = Matrix::streamed(vec![(x, x_factor), (y, y_factor)]);
+        let missing_values = props.missing_values;
+        self.push(
+            Plot::new(axes, &[Coordinate::X, Coordinate::Y], data, &props),
+            missing_values,
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::prelude::*;
+
+    #[test]
+    fn curves_are_downsampled_to_the_figure_width() {
+        let x = (0..10_000).map(f64::from).collect::<Vec<_>>();
+
+        let mut figure = Figure::new();
+        figure
+            .figure_size(100, 100)
+            .downsampling(Downsampling::Lttb)
+            .plot(Lines { x: &x, y: &x }, |l| l)
+            .plot(
+                Lines {
+                    x: Column::from(x.clone()),
+                    y: Column::from(x.clone()),
+                },
+                |l| l.downsampling(Downsampling::MinMax),
+            )
+            .plot(Lines { x: &x, y: &x }, |l| {
+                l.downsampling(Downsampling::None)
+            });
+
+        let script = figure.script();
+        assert!(script.contains("record=100 "));
+        assert!(script.contains("record=200 "));
+        assert!(script.contains("record=10000 "));
+    }
+
+    #[test]
+    fn smoothed_curves_arent_downsampled() {
+        let x = (0..10_000).map(f64::from).collect::<Vec<_>>();
+
+        let mut figure = Figure::new();
+        figure
+            .figure_size(100, 100)
+            .downsampling(Downsampling::MinMax)
+            .plot(Lines { x: &x, y: &x }, |l| l.smooth(Smooth::Cumulative))
+            .plot(
+                Lines {
+                    x: Column::from(x.clone()),
+                    y: Column::from(x.clone()),
+                },
+                |l| l.smooth(Smooth::Frequency),
+            );
+
+        let script = figure.script();
+        assert!(!script.contains("record=200 "));
+        assert_eq!(script.matches("record=10000 ").count(), 2);
+    }
+
+    #[test]
+    fn smoothed_curves() {
+        let mut figure = Figure::new();
+        figure
+            .plot(
+                Lines {
+                    x: &[3., 1., 2.],
+                    y: &[1., 1., 1.],
+                },
+                |l| l.axes(Axes::BottomXRightY).smooth(Smooth::Cnormal),
+            )
+            .plot(
+                HiSteps {
+                    x: &[1., 2.],
+                    y: &[2., 3.],
+                },
+                |s| s,
+            );
+        let script = figure.script();
+
+        assert!(script.contains("smooth cnormal axes x1y2 with lines lc rgb"));
+        assert!(script.contains("with histeps lc rgb '#009e73' notitle"));
     }
 }
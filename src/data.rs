@@ -1,11 +1,14 @@
 #![allow(deprecated)]
 
+use std::borrow::Cow;
+use std::io;
 use std::mem;
 
-use byteorder::{LittleEndian, WriteBytesExt};
+use byteorder::{ByteOrder, LittleEndian, WriteBytesExt};
 use cast::From as _0;
 
 use crate::traits::Data;
+use crate::MissingValues;
 
 macro_rules! impl_data {
     ($($ty:ty),+) => {
@@ -27,13 +30,43 @@ macro_rules! impl_data {
 
 impl_data!(f32, f64, i16, i32, i64, i8, isize, u16, u32, u64, u8, usize);
 
+/// `None` marks a missing value
+impl<T> Data for Option<T>
+where
+    T: Data,
+{
+    fn f64(self) -> f64 {
+        self.map_or(f64::NAN, Data::f64)
+    }
+}
+
+impl<'a, T> Data for &'a Option<T>
+where
+    &'a T: Data,
+{
+    fn f64(self) -> f64 {
+        self.as_ref().map_or(f64::NAN, Data::f64)
+    }
+}
+
+#[derive(Clone)]
+enum Storage {
+    /// Rows of little-endian `f64`s
+    Bytes(Vec<u8>),
+    /// Columns that get scaled and interleaved as they are written, along with their scale factor
+    Columns(Vec<(Cow<'static, [f64]>, f64)>),
+}
+
 #[derive(Clone)]
 pub struct Matrix {
-    bytes: Vec<u8>,
+    storage: Storage,
     ncols: usize,
     nrows: usize,
 }
 
+/// Number of rows that are interleaved at once when streaming columns
+const ROWS_PER_CHUNK: usize = 4096;
+
 impl Matrix {
     pub fn new<I>(rows: I, scale: <I::Item as Row>::Scale) -> Matrix
     where
@@ -51,14 +84,210 @@ impl Matrix {
         }
 
         Matrix {
-            bytes,
+            storage: Storage::Bytes(bytes),
+            ncols,
+            nrows,
+        }
+    }
+
+    /// Builds the matrix from column-major data, each column is scaled by its `scale` factor
+    ///
+    /// **Note** The number of rows is the length of the shortest column
+    pub fn from_columns(columns: &[&[f64]], scale: &[f64]) -> Matrix {
+        assert_eq!(columns.len(), scale.len());
+
+        let ncols = columns.len();
+        let nrows = columns.iter().map(|col| col.len()).min().unwrap_or(0);
+        let mut bytes = Vec::with_capacity(nrows * ncols * mem::size_of::<f64>());
+
+        for row in 0..nrows {
+            for (col, scale) in columns.iter().zip(scale) {
+                bytes.write_f64::<LittleEndian>(col[row] * scale).unwrap();
+            }
+        }
+
+        Matrix {
+            storage: Storage::Bytes(bytes),
+            ncols,
+            nrows,
+        }
+    }
+
+    /// Builds the matrix from columns that are kept as they are, and only get scaled and
+    /// interleaved when the matrix is written
+    ///
+    /// **Note** The number of rows is the length of the shortest column
+    pub fn streamed(columns: Vec<(Cow<'static, [f64]>, f64)>) -> Matrix {
+        let ncols = columns.len();
+        let nrows = columns.iter().map(|(col, _)| col.len()).min().unwrap_or(0);
+
+        Matrix {
+            storage: Storage::Columns(columns),
             ncols,
             nrows,
         }
     }
 
-    pub fn bytes(&self) -> &[u8] {
-        &self.bytes
+    /// Writes the rows of the matrix as little-endian `f64`s
+    pub fn write_to<W>(&self, sink: &mut W) -> io::Result<()>
+    where
+        W: io::Write,
+    {
+        match self.storage {
+            Storage::Bytes(ref bytes) => sink.write_all(bytes),
+            Storage::Columns(ref columns) => {
+                let size = mem::size_of::<f64>();
+                let mut buffer = Vec::with_capacity(ROWS_PER_CHUNK * self.ncols * size);
+
+                for start in (0..self.nrows).step_by(ROWS_PER_CHUNK) {
+                    let end = (start + ROWS_PER_CHUNK).min(self.nrows);
+
+                    buffer.clear();
+                    for row in start..end {
+                        for (col, scale) in columns {
+                            // The missing values that break the lines are left in the columns
+                            let value = col[row] * scale;
+                            let value = if value.is_finite() { value } else { f64::NAN };
+
+                            buffer.extend_from_slice(&value.to_le_bytes());
+                        }
+                    }
+
+                    sink.write_all(&buffer)?;
+                }
+
+                Ok(())
+            }
+        }
+    }
+
+    /// Iterates over the values stored in the column `col`
+    pub fn column(&self, col: usize) -> Box<dyn Iterator<Item = f64> + '_> {
+        match self.storage {
+            Storage::Bytes(ref bytes) => {
+                let size = mem::size_of::<f64>();
+
+                Box::new(
+                    bytes
+                        .chunks(self.ncols * size)
+                        .map(move |row| LittleEndian::read_f64(&row[col * size..])),
+                )
+            }
+            Storage::Columns(ref columns) => {
+                let (ref values, scale) = columns[col];
+
+                Box::new(values[..self.nrows].iter().map(move |x| x * scale))
+            }
+        }
+    }
+
+    /// Minimum and maximum of the finite values stored in the column `col`
+    pub fn column_bounds(&self, col: usize) -> Option<(f64, f64)> {
+        self.column(col)
+            .filter(|x| x.is_finite())
+            .fold(None, |bounds, x| match bounds {
+                None => Some((x, x)),
+                Some((low, high)) => Some((x.min(low), x.max(high))),
+            })
+    }
+
+    /// Minimum and maximum reached by the values of the column `center` when they are extended on
+    /// both sides by the values of the column `delta`
+    pub fn delta_bounds(&self, center: usize, delta: usize) -> Option<(f64, f64)> {
+        self.column(center)
+            .zip(self.column(delta))
+            .filter(|(c, d)| c.is_finite() && d.is_finite())
+            .fold(None, |bounds, (c, d)| {
+                let (low, high) = (c - d.abs(), c + d.abs());
+
+                match bounds {
+                    None => Some((low, high)),
+                    Some((min, max)) => Some((low.min(min), high.max(max))),
+                }
+            })
+    }
+
+    /// Applies the missing value `policy` to the rows that contain non-finite values, `x` is the
+    /// column the other columns are interpolated against. Returns the number of rows that won't
+    /// be drawn, with `MissingValues::Error` those are the rows that contain missing values
+    ///
+    /// **Note** Matrices without missing values are left untouched, and so are streamed columns
+    /// whose lines are broken: their non-finite values are written as `NaN`s
+    pub fn clean(&mut self, policy: MissingValues, x: Option<usize>) -> usize {
+        let mut is_missing = vec![false; self.nrows];
+        for col in 0..self.ncols {
+            for (row, value) in self.column(col).enumerate() {
+                if !value.is_finite() {
+                    is_missing[row] = true;
+                }
+            }
+        }
+
+        let missing = is_missing.iter().filter(|&&missing| missing).count();
+        if missing == 0 {
+            return 0;
+        }
+
+        match policy {
+            MissingValues::Break => {
+                // Broken lines keep their rows, gnuplot doesn't draw the undefined points
+                if let Storage::Bytes(ref mut bytes) = self.storage {
+                    for value in bytes.chunks_mut(mem::size_of::<f64>()) {
+                        if !LittleEndian::read_f64(value).is_finite() {
+                            LittleEndian::write_f64(value, f64::NAN);
+                        }
+                    }
+                }
+
+                return missing;
+            }
+            MissingValues::Error => return missing,
+            MissingValues::Interpolate | MissingValues::Skip => {}
+        }
+
+        let mut columns = (0..self.ncols)
+            .map(|col| self.column(col).collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+
+        if let (MissingValues::Interpolate, Some(x)) = (policy, x) {
+            let xs = columns[x].clone();
+
+            for (col, values) in columns.iter_mut().enumerate() {
+                if col != x {
+                    interpolate(&xs, values);
+                }
+            }
+
+            for (row, is_missing) in is_missing.iter_mut().enumerate() {
+                *is_missing = columns.iter().any(|col| !col[row].is_finite());
+            }
+        }
+
+        let rows = (0..self.nrows)
+            .filter(|&row| !is_missing[row])
+            .collect::<Vec<_>>();
+        let dropped = self.nrows - rows.len();
+        let columns = columns
+            .iter()
+            .map(|col| rows.iter().map(|&row| col[row]).collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+
+        // The columns are already scaled
+        *self = match self.storage {
+            Storage::Bytes(_) => {
+                let columns = columns.iter().map(|col| &col[..]).collect::<Vec<_>>();
+
+                Matrix::from_columns(&columns, &vec![1.; self.ncols])
+            }
+            Storage::Columns(_) => Matrix::streamed(
+                columns
+                    .into_iter()
+                    .map(|col| (Cow::Owned(col), 1.))
+                    .collect(),
+            ),
+        };
+
+        dropped
     }
 
     pub fn ncols(&self) -> usize {
@@ -70,6 +299,36 @@ impl Matrix {
     }
 }
 
+/// Linearly interpolates the non-finite `values` between their finite neighbors, values that
+/// don't have finite neighbors on both sides are left as they are
+fn interpolate(xs: &[f64], values: &mut [f64]) {
+    let mut previous = None;
+    let mut next = None;
+
+    for row in 0..values.len() {
+        if !xs[row].is_finite() {
+            continue;
+        }
+
+        if values[row].is_finite() {
+            previous = Some(row);
+            continue;
+        }
+
+        if next.map_or(true, |next| next <= row) {
+            next = (row + 1..values.len()).find(|&i| xs[i].is_finite() && values[i].is_finite());
+        }
+
+        if let (Some(a), Some(b)) = (previous, next) {
+            let t = (xs[row] - xs[a]) / (xs[b] - xs[a]);
+
+            if t.is_finite() {
+                values[row] = values[a] + t * (values[b] - values[a]);
+            }
+        }
+    }
+}
+
 /// Data that can serve as a row of the data matrix
 pub trait Row {
     /// Private
@@ -81,91 +340,200 @@ pub trait Row {
     fn ncols() -> usize;
 }
 
-impl<A, B> Row for (A, B)
-where
-    A: Data,
-    B: Data,
-{
-    type Scale = (f64, f64);
+macro_rules! impl_row {
+    ($ncols:expr; $($ty:ident.$i:tt),+) => {
+        impl<$($ty),+> Row for ($($ty,)+)
+        where
+            $($ty: Data,)+
+        {
+            type Scale = ($(impl_row!(@f64 $ty),)+);
+
+            fn append_to(self, buffer: &mut Vec<u8>, scale: Self::Scale) {
+                $(
+                    buffer.write_f64::<LittleEndian>(self.$i.f64() * scale.$i).unwrap();
+                )+
+            }
+
+            fn ncols() -> usize {
+                $ncols
+            }
+        }
+    };
+    (@f64 $ty:ident) => { f64 };
+}
 
-    fn append_to(self, buffer: &mut Vec<u8>, scale: (f64, f64)) {
-        let (a, b) = self;
+impl_row!(2; A.0, B.1);
+impl_row!(3; A.0, B.1, C.2);
+impl_row!(4; A.0, B.1, C.2, D.3);
+impl_row!(5; A.0, B.1, C.2, D.3, E.4);
+impl_row!(6; A.0, B.1, C.2, D.3, E.4, F.5);
+impl_row!(7; A.0, B.1, C.2, D.3, E.4, F.5, G.6);
+impl_row!(8; A.0, B.1, C.2, D.3, E.4, F.5, G.6, H.7);
 
-        buffer.write_f64::<LittleEndian>(a.f64() * scale.0).unwrap();
-        buffer.write_f64::<LittleEndian>(b.f64() * scale.1).unwrap();
+#[cfg(test)]
+mod test {
+    use std::borrow::Cow;
+
+    use super::{Matrix, Storage, ROWS_PER_CHUNK};
+    use crate::prelude::*;
+    use crate::Warning;
+
+    fn bytes(matrix: &Matrix) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        matrix.write_to(&mut bytes).unwrap();
+        bytes
     }
 
-    fn ncols() -> usize {
-        2
+    #[test]
+    fn rows_and_columns_produce_the_same_matrix() {
+        let (x, y) = ([1., 2., 3.], [4., 5., 6.]);
+
+        let tuples = Matrix::new(x.iter().zip(y.iter()), (1., 2.));
+        let columns = Matrix::from_columns(&[&x, &y], &[1., 2.]);
+
+        assert_eq!(bytes(&tuples), bytes(&columns));
+        assert_eq!((3, 2), (columns.nrows(), columns.ncols()));
+        assert_eq!(vec![8., 10., 12.], columns.column(1).collect::<Vec<_>>());
     }
-}
 
-impl<A, B, C> Row for (A, B, C)
-where
-    A: Data,
-    B: Data,
-    C: Data,
-{
-    type Scale = (f64, f64, f64);
+    #[test]
+    fn streamed_columns_are_interleaved_on_write() {
+        let n = 2 * ROWS_PER_CHUNK + 1;
+        let x = (0..n).map(|i| i as f64).collect::<Vec<_>>();
+        let y = x.iter().map(|x| x * x).collect::<Vec<_>>();
 
-    fn append_to(self, buffer: &mut Vec<u8>, scale: (f64, f64, f64)) {
-        let (a, b, c) = self;
+        let copied = Matrix::from_columns(&[&x, &y], &[1., 0.5]);
+        let streamed = Matrix::streamed(vec![(Cow::Owned(x), 1.), (Cow::Owned(y), 0.5)]);
 
-        buffer.write_f64::<LittleEndian>(a.f64() * scale.0).unwrap();
-        buffer.write_f64::<LittleEndian>(b.f64() * scale.1).unwrap();
-        buffer.write_f64::<LittleEndian>(c.f64() * scale.2).unwrap();
+        assert_eq!(n, streamed.nrows());
+        assert_eq!(bytes(&copied), bytes(&streamed));
+        assert_eq!(copied.column_bounds(1), streamed.column_bounds(1));
     }
 
-    fn ncols() -> usize {
-        3
+    #[test]
+    fn missing_values() {
+        let x = [0., 1., 2., 3., 4.];
+        let y = [f64::NAN, 1., f64::INFINITY, 3., 4.];
+        let matrix = Matrix::from_columns(&[&x, &y], &[1., 1.]);
+        let clean = |policy| {
+            let mut matrix = matrix.clone();
+            let dropped = matrix.clean(policy, Some(0));
+            (dropped, matrix.column(1).collect::<Vec<_>>())
+        };
+
+        let (dropped, broken) = clean(MissingValues::Break);
+        assert_eq!(2, dropped);
+        assert!(broken[0].is_nan() && broken[2].is_nan());
+        assert_eq!((2, vec![1., 3., 4.]), clean(MissingValues::Skip));
+        assert_eq!((1, vec![1., 2., 3., 4.]), clean(MissingValues::Interpolate));
     }
-}
 
-impl<A, B, C, D> Row for (A, B, C, D)
-where
-    A: Data,
-    B: Data,
-    C: Data,
-    D: Data,
-{
-    type Scale = (f64, f64, f64, f64);
+    #[test]
+    fn streamed_columns_stay_streamed_when_broken() {
+        let y = vec![1., f64::NEG_INFINITY, 3.];
+        let mut matrix = Matrix::streamed(vec![(Cow::Owned(y), 2.)]);
 
-    fn append_to(self, buffer: &mut Vec<u8>, scale: (f64, f64, f64, f64)) {
-        let (a, b, c, d) = self;
+        assert_eq!(1, matrix.clean(MissingValues::Break, None));
+        assert!(matches!(matrix.storage, Storage::Columns(_)));
 
-        buffer.write_f64::<LittleEndian>(a.f64() * scale.0).unwrap();
-        buffer.write_f64::<LittleEndian>(b.f64() * scale.1).unwrap();
-        buffer.write_f64::<LittleEndian>(c.f64() * scale.2).unwrap();
-        buffer.write_f64::<LittleEndian>(d.f64() * scale.3).unwrap();
+        let broken = Matrix::from_columns(&[&[2., f64::NAN, 6.]], &[1.]);
+        assert_eq!(bytes(&broken), bytes(&matrix));
     }
 
-    fn ncols() -> usize {
-        4
+    #[test]
+    fn missing_values_error() {
+        let x = [0., f64::NAN];
+        let mut matrix = Matrix::from_columns(&[&x, &x], &[1., 1.]);
+
+        assert_eq!(1, matrix.clean(MissingValues::Error, Some(0)));
+        assert_eq!(2, matrix.nrows());
     }
-}
 
-impl<A, B, C, D, E> Row for (A, B, C, D, E)
-where
-    A: Data,
-    B: Data,
-    C: Data,
-    D: Data,
-    E: Data,
-{
-    type Scale = (f64, f64, f64, f64, f64);
+    #[test]
+    fn gaps_are_reported() {
+        let mut figure = Figure::new();
+        figure
+            .plot(
+                Lines {
+                    x: &[1., 2., 3.],
+                    y: &[Some(1.), None, Some(3.)],
+                },
+                |l| l,
+            )
+            .plot(
+                Lines {
+                    x: &[1., 2., 3.],
+                    y: &[Some(1.), None, None],
+                },
+                |l| l.missing_values(MissingValues::Interpolate),
+            );
 
-    #[allow(clippy::many_single_char_names)]
-    fn append_to(self, buffer: &mut Vec<u8>, scale: (f64, f64, f64, f64, f64)) {
-        let (a, b, c, d, e) = self;
+        assert!(figure.script().contains("record=3 "));
+        assert!(figure.script().contains("record=1 "));
+        match &figure.warnings()[..] {
+            [Warning::MissingValues { points: 3 }] => {}
+            _ => panic!(),
+        }
+    }
+
+    #[test]
+    fn broken_lines_mark_the_nans_as_missing() {
+        let mut figure = Figure::new();
+        figure.plot(
+            Lines {
+                x: &[1., 2.],
+                y: &[1., 2.],
+            },
+            |l| l,
+        );
+        assert!(!figure.script().contains("set datafile missing"));
 
-        buffer.write_f64::<LittleEndian>(a.f64() * scale.0).unwrap();
-        buffer.write_f64::<LittleEndian>(b.f64() * scale.1).unwrap();
-        buffer.write_f64::<LittleEndian>(c.f64() * scale.2).unwrap();
-        buffer.write_f64::<LittleEndian>(d.f64() * scale.3).unwrap();
-        buffer.write_f64::<LittleEndian>(e.f64() * scale.4).unwrap();
+        figure.plot(
+            Lines {
+                x: Column::from(vec![1., 2., 3.]),
+                y: Column::from(vec![1., f64::INFINITY, 3.]),
+            },
+            |l| l,
+        );
+        assert!(figure.script().contains("\nset datafile missing NaN\n"));
     }
 
-    fn ncols() -> usize {
-        5
+    #[test]
+    fn missing_values_can_be_rejected() {
+        let mut figure = Figure::new();
+        figure.missing_values(MissingValues::Error).plot(
+            Lines {
+                x: &[1., 2.],
+                y: &[Some(1.), None],
+            },
+            |l| l,
+        );
+
+        let err = figure.dump(&mut Vec::new()).err().unwrap();
+        assert_eq!(std::io::ErrorKind::InvalidData, err.kind());
+        assert!(figure.warnings().is_empty());
+    }
+
+    #[test]
+    fn columns_are_streamed_after_the_script() {
+        let mut figure = Figure::new();
+        figure.plot(
+            Lines {
+                x: Column::from(vec![1., 2., 3.]),
+                y: Column::from(vec![4., 5., 6.]),
+            },
+            |l| l,
+        );
+
+        let mut output = Vec::new();
+        figure.dump(&mut output).unwrap();
+
+        let script = figure.script();
+        assert!(script.contains("'-' binary endian=little record=3 format='%float64' using 1:2 "));
+        assert_eq!(script.len() + 6 * 8, output.len());
+        assert_eq!(
+            &2f64.to_le_bytes()[..],
+            &output[script.len() + 16..script.len() + 24]
+        );
     }
 }
@@ -0,0 +1,169 @@
+//! Deserialization of the properties whose setters check their values
+//!
+//! Deserialized values don't go through the setters, so the fields that are checked by a setter
+//! are deserialized with one of these functions: an invalid value is reported as a deserialization
+//! error, instead of panicking later or producing an invalid script.
+
+use serde::de::{Deserialize, Deserializer, Error, Unexpected};
+
+use crate::axis::{Range, Scale};
+use crate::{Caps, Color, LineType};
+
+/// Values that some setters reject
+pub(crate) trait Check {
+    /// Describes the valid values if `self` isn't one of them
+    fn check(&self) -> Result<(), &'static str>;
+}
+
+impl<T> Check for Option<T>
+where
+    T: Check,
+{
+    fn check(&self) -> Result<(), &'static str> {
+        match self {
+            Some(value) => value.check(),
+            None => Ok(()),
+        }
+    }
+}
+
+impl Check for Caps {
+    fn check(&self) -> Result<(), &'static str> {
+        match *self {
+            Caps::Size(size) if size.is_nan() || size < 0. => Err("a non-negative cap size"),
+            _ => Ok(()),
+        }
+    }
+}
+
+impl Check for LineType {
+    fn check(&self) -> Result<(), &'static str> {
+        if self.is_valid() {
+            Ok(())
+        } else {
+            Err("a dash pattern made of 1 to 4 non-negative `(dash, gap)` pairs")
+        }
+    }
+}
+
+impl Check for Range {
+    fn check(&self) -> Result<(), &'static str> {
+        match *self {
+            Range::Padded(margin) if margin.is_nan() || margin < 0. => Err("a non-negative margin"),
+            _ => Ok(()),
+        }
+    }
+}
+
+impl Check for Scale {
+    fn check(&self) -> Result<(), &'static str> {
+        match *self {
+            Scale::LogarithmicBase(base) if !(base > 0. && (base - 1.).abs() > f64::EPSILON) => {
+                Err("a positive logarithm base other than 1")
+            }
+            Scale::SymmetricLog(threshold) if threshold.is_nan() || threshold <= 0. => {
+                Err("a positive threshold")
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+impl Check for Vec<Color> {
+    fn check(&self) -> Result<(), &'static str> {
+        if self.is_empty() {
+            Err("at least one color")
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// Deserializes a value and checks it
+pub(crate) fn checked<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Check + Deserialize<'de>,
+{
+    let value = T::deserialize(deserializer)?;
+
+    match value.check() {
+        Ok(()) => Ok(value),
+        Err(expected) => Err(D::Error::custom(format_args!(
+            "invalid value, expected {}",
+            expected
+        ))),
+    }
+}
+
+/// Numbers that are optional or not
+pub(crate) trait Number {
+    fn number(&self) -> Option<f64>;
+}
+
+impl Number for f64 {
+    fn number(&self) -> Option<f64> {
+        Some(*self)
+    }
+}
+
+impl Number for Option<f64> {
+    fn number(&self) -> Option<f64> {
+        *self
+    }
+}
+
+impl Number for Option<usize> {
+    fn number(&self) -> Option<f64> {
+        self.map(|n| n as f64)
+    }
+}
+
+/// Deserializes a number and checks that it's `valid`
+pub(crate) fn number<'de, D, T>(
+    deserializer: D,
+    valid: fn(f64) -> bool,
+    expected: &'static str,
+) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de> + Number,
+{
+    let value = T::deserialize(deserializer)?;
+
+    match value.number() {
+        Some(x) if !valid(x) => Err(D::Error::invalid_value(Unexpected::Float(x), &expected)),
+        _ => Ok(value),
+    }
+}
+
+/// Deserializes a width or a size, which must be positive
+pub(crate) fn positive<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de> + Number,
+{
+    number(deserializer, |x| x > 0., "a positive value")
+}
+
+/// Deserializes a width or a size that can be zero
+pub(crate) fn non_negative<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de> + Number,
+{
+    number(deserializer, |x| x >= 0., "a non-negative value")
+}
+
+/// Deserializes an opacity, within `[0, 1]`
+pub(crate) fn fraction<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de> + Number,
+{
+    number(
+        deserializer,
+        |x| (0. ..=1.).contains(&x),
+        "a value within [0, 1]",
+    )
+}
@@ -1,7 +1,9 @@
 use std::borrow::Cow;
 
+use crate::curve::Smooth;
 use crate::key::{Horizontal, Justification, Order, Stacked, Vertical};
-use crate::{Axes, Axis, Color, Display, LineType, PointType, Terminal};
+use crate::text::quote;
+use crate::{Axes, Axis, Caps, Color, Display, Layer, LineType, PointType, Terminal};
 
 impl Display<&'static str> for Axis {
     fn display(&self) -> &'static str {
@@ -25,6 +27,17 @@ impl Display<&'static str> for Axes {
     }
 }
 
+impl Display<Cow<'static, str>> for Caps {
+    fn display(&self) -> Cow<'static, str> {
+        match *self {
+            Caps::None => Cow::from("0"),
+            Caps::Small => Cow::from("small"),
+            Caps::Large => Cow::from("large"),
+            Caps::Size(size) => Cow::from(size.to_string()),
+        }
+    }
+}
+
 impl Display<Cow<'static, str>> for Color {
     fn display(&self) -> Cow<'static, str> {
         match *self {
@@ -39,6 +52,9 @@ impl Display<Cow<'static, str>> for Color {
             Color::Magenta => Cow::from("magenta"),
             Color::Red => Cow::from("red"),
             Color::Rgb(r, g, b) => Cow::from(format!("#{:02x}{:02x}{:02x}", r, g, b)),
+            Color::Rgba(r, g, b, a) => {
+                Cow::from(format!("#{:02x}{:02x}{:02x}{:02x}", 0xff - a, r, g, b))
+            }
             Color::White => Cow::from("white"),
             Color::Yellow => Cow::from("yellow"),
         }
@@ -64,15 +80,32 @@ impl Display<&'static str> for Justification {
     }
 }
 
-impl Display<&'static str> for LineType {
+impl Display<&'static str> for Layer {
     fn display(&self) -> &'static str {
         match *self {
-            LineType::Dash => "2",
-            LineType::Dot => "3",
-            LineType::DotDash => "4",
-            LineType::DotDotDash => "5",
-            LineType::SmallDot => "0",
-            LineType::Solid => "1",
+            Layer::Back => "back",
+            Layer::Front => "front",
+        }
+    }
+}
+
+impl Display<Cow<'static, str>> for LineType {
+    fn display(&self) -> Cow<'static, str> {
+        match *self {
+            LineType::Custom(ref pattern) => Cow::from(format!(
+                "({})",
+                pattern
+                    .iter()
+                    .map(|(dash, gap)| format!("{},{}", dash, gap))
+                    .collect::<Vec<_>>()
+                    .join(",")
+            )),
+            LineType::Dash => Cow::from("(8,4)"),
+            LineType::Dot => Cow::from("(1,4)"),
+            LineType::DotDash => Cow::from("(8,4,1,4)"),
+            LineType::DotDotDash => Cow::from("(8,4,1,4,1,4)"),
+            LineType::SmallDot => Cow::from("(1,2)"),
+            LineType::Solid => Cow::from("solid"),
         }
     }
 }
@@ -86,18 +119,38 @@ impl Display<&'static str> for Order {
     }
 }
 
-impl Display<&'static str> for PointType {
-    fn display(&self) -> &'static str {
-        match *self {
+impl Display<Cow<'static, str>> for PointType {
+    fn display(&self) -> Cow<'static, str> {
+        Cow::from(match *self {
             PointType::Circle => "6",
+            PointType::Diamond => "12",
             PointType::FilledCircle => "7",
+            PointType::FilledDiamond => "13",
+            PointType::FilledPentagon => "15",
             PointType::FilledSquare => "5",
             PointType::FilledTriangle => "9",
+            PointType::Glyph(c) => return Cow::from(quote(&c.to_string())),
+            PointType::Pentagon => "14",
             PointType::Plus => "1",
             PointType::Square => "4",
             PointType::Star => "3",
             PointType::Triangle => "8",
             PointType::X => "2",
+        })
+    }
+}
+
+impl Display<&'static str> for Smooth {
+    fn display(&self) -> &'static str {
+        match *self {
+            Smooth::Acsplines => "acsplines",
+            Smooth::Bezier => "bezier",
+            Smooth::Cnormal => "cnormal",
+            Smooth::Csplines => "csplines",
+            Smooth::Cumulative => "cumulative",
+            Smooth::Frequency => "frequency",
+            Smooth::Kdensity => "kdensity",
+            Smooth::Unique => "unique",
         }
     }
 }
@@ -114,7 +167,10 @@ impl Display<&'static str> for Stacked {
 impl Display<&'static str> for Terminal {
     fn display(&self) -> &'static str {
         match *self {
+            Terminal::CairoLatex => "cairolatex pdf",
+            Terminal::Png => "pngcairo",
             Terminal::Svg => "svg dynamic",
+            Terminal::Tikz => "tikz",
         }
     }
 }
@@ -0,0 +1,148 @@
+//! Decimation of large series
+
+use crate::Downsampling;
+
+impl Downsampling {
+    /// Indices of the points that are kept when the series is drawn `width` pixels wide, `None`
+    /// if the series doesn't need to be downsampled
+    pub(crate) fn indices(self, x: &[f64], y: &[f64], width: usize) -> Option<Vec<usize>> {
+        let n = x.len().min(y.len());
+
+        match self {
+            Downsampling::None => None,
+            Downsampling::Lttb if width >= 3 && n > width => Some(lttb(x, y, n, width)),
+            Downsampling::MinMax if width >= 1 && n > 2 * width => Some(min_max(x, y, n, width)),
+            Downsampling::Lttb | Downsampling::MinMax => None,
+        }
+    }
+}
+
+/// Picks the values at `indices`
+pub(crate) fn select(values: &[f64], indices: &[usize]) -> Vec<f64> {
+    indices.iter().map(|&i| values[i]).collect()
+}
+
+/// Largest-Triangle-Three-Buckets: keeps `threshold` points, picking from each bucket the point
+/// that forms the largest triangle with the previously kept point and the average of the next
+/// bucket
+fn lttb(x: &[f64], y: &[f64], n: usize, threshold: usize) -> Vec<usize> {
+    let every = (n - 2) as f64 / (threshold - 2) as f64;
+    let mut kept = Vec::with_capacity(threshold);
+    let mut a = 0;
+
+    kept.push(a);
+    for bucket in 0..threshold - 2 {
+        let next_start = ((bucket + 1) as f64 * every) as usize + 1;
+        let next_end = (((bucket + 2) as f64 * every) as usize + 1).min(n);
+        let next_len = (next_end - next_start) as f64;
+        let avg_x = x[next_start..next_end].iter().sum::<f64>() / next_len;
+        let avg_y = y[next_start..next_end].iter().sum::<f64>() / next_len;
+
+        let start = (bucket as f64 * every) as usize + 1;
+        let end = next_start;
+
+        let mut max_area = -1.;
+        let mut next_a = start;
+        for i in start..end {
+            let area = ((x[a] - avg_x) * (y[i] - y[a]) - (x[a] - x[i]) * (avg_y - y[a])).abs();
+
+            if area > max_area {
+                max_area = area;
+                next_a = i;
+            }
+        }
+
+        kept.push(next_a);
+        a = next_a;
+    }
+    kept.push(n - 1);
+
+    kept
+}
+
+/// Splits the x range in `width` buckets (one per pixel) and keeps the lowest and highest point
+/// of each bucket
+fn min_max(x: &[f64], y: &[f64], n: usize, width: usize) -> Vec<usize> {
+    let (low, high) = x[..n]
+        .iter()
+        .filter(|x| x.is_finite())
+        .fold((f64::INFINITY, f64::NEG_INFINITY), |(low, high), &x| {
+            (low.min(x), high.max(x))
+        });
+
+    if high <= low {
+        return (0..n).collect();
+    }
+
+    let mut buckets: Vec<Option<(usize, usize)>> = vec![None; width];
+    for i in 0..n {
+        if !x[i].is_finite() || !y[i].is_finite() {
+            continue;
+        }
+
+        let bucket = (((x[i] - low) / (high - low) * width as f64) as usize).min(width - 1);
+        buckets[bucket] = Some(match buckets[bucket] {
+            None => (i, i),
+            Some((min, max)) => (
+                if y[i] < y[min] { i } else { min },
+                if y[i] > y[max] { i } else { max },
+            ),
+        });
+    }
+
+    let mut kept = vec![0, n - 1];
+    for &(min, max) in buckets.iter().flatten() {
+        kept.push(min);
+        kept.push(max);
+    }
+    kept.sort_unstable();
+    kept.dedup();
+
+    kept
+}
+
+#[cfg(test)]
+mod test {
+    use crate::Downsampling;
+
+    #[test]
+    fn lttb_keeps_the_endpoints_and_the_peak() {
+        let x = (0..1000).map(f64::from).collect::<Vec<_>>();
+        let y = x
+            .iter()
+            .map(|&x| if x == 500. { 100. } else { 0. })
+            .collect::<Vec<_>>();
+
+        let kept = Downsampling::Lttb.indices(&x, &y, 50).unwrap();
+
+        assert_eq!(50, kept.len());
+        assert_eq!(Some(&0), kept.first());
+        assert_eq!(Some(&999), kept.last());
+        assert!(kept.contains(&500));
+    }
+
+    #[test]
+    fn min_max_keeps_the_extremes_of_each_pixel() {
+        let x = (0..1000).map(f64::from).collect::<Vec<_>>();
+        let y = x.iter().map(|x| (x / 10.).sin()).collect::<Vec<_>>();
+
+        let kept = Downsampling::MinMax.indices(&x, &y, 10).unwrap();
+
+        assert!(kept.len() <= 2 * 10 + 2);
+        assert!(kept.windows(2).all(|w| w[0] < w[1]));
+        for bucket in x.chunks(100).zip(y.chunks(100)) {
+            let (_, ys) = bucket;
+            let max = ys.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+            assert!(kept.iter().any(|&i| y[i] == max));
+        }
+    }
+
+    #[test]
+    fn short_series_are_untouched() {
+        let x = [0., 1., 2.];
+
+        assert!(Downsampling::Lttb.indices(&x, &x, 10).is_none());
+        assert!(Downsampling::MinMax.indices(&x, &x, 10).is_none());
+        assert!(Downsampling::None.indices(&x, &x, 1).is_none());
+    }
+}
@@ -0,0 +1,328 @@
+//! Confidence band plots
+
+use itertools::izip;
+use std::iter::IntoIterator;
+
+use crate::data::Matrix;
+use crate::traits::{self, Data};
+use crate::{
+    Axes, Color, Coordinate, Default, Display, Figure, LineStyleId, LineType, Plot, Script, Text,
+};
+
+/// Properties of the error band plots
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(feature = "serde", serde(default = "Default::default"))]
+pub struct Properties {
+    axes: Option<Axes>,
+    color: Option<Color>,
+    label: Option<Text>,
+    // The line styles of a figure aren't deserialized
+    #[cfg_attr(feature = "serde", serde(skip))]
+    line_style: Option<LineStyleId>,
+    #[cfg_attr(feature = "serde", serde(deserialize_with = "crate::de::checked"))]
+    line_type: Option<LineType>,
+    #[cfg_attr(feature = "serde", serde(deserialize_with = "crate::de::positive"))]
+    linewidth: Option<f64>,
+    #[cfg_attr(feature = "serde", serde(deserialize_with = "crate::de::fraction"))]
+    opacity: f64,
+}
+
+impl Properties {
+    /// Select axes to plot against
+    ///
+    /// **Note** By default, the `BottomXLeftY` axes are used
+    pub fn axes(&mut self, axes: Axes) -> &mut Properties {
+        self.axes = Some(axes);
+        self
+    }
+
+    /// Sets the color of both the line and the band
+    ///
+    /// **Note** By default, the line and the band take the next color of the figure's color cycle
+    pub fn color(&mut self, color: Color) -> &mut Properties {
+        self.color = Some(color);
+        self
+    }
+
+    /// Sets the legend label
+    pub fn label<S>(&mut self, label: S) -> &mut Properties
+    where
+        S: Into<Text>,
+    {
+        self.label = Some(label.into());
+        self
+    }
+
+    /// References a line style defined with [`Figure::line_style`], the properties set on the
+    /// plot take precedence over the ones of the style. The band takes the color of the style
+    ///
+    /// [`Figure::line_style`]: ../struct.Figure.html#method.line_style
+    pub fn line_style(&mut self, style: LineStyleId) -> &mut Properties {
+        self.line_style = Some(style);
+        self
+    }
+
+    /// Changes the line type of the center line
+    ///
+    /// **Note** By default `Solid` lines are used, or the line type of the line style
+    ///
+    /// # Panics
+    ///
+    /// Panics if a `Custom` dash pattern is invalid
+    pub fn line_type(&mut self, lt: LineType) -> &mut Properties {
+        lt.check();
+
+        self.line_type = Some(lt);
+        self
+    }
+
+    /// Changes the width of the center line
+    ///
+    /// # Panics
+    ///
+    /// Panics if `width` is a non-positive value
+    pub fn line_width(&mut self, lw: f64) -> &mut Properties {
+        assert!(lw > 0.);
+
+        self.linewidth = Some(lw);
+        self
+    }
+
+    /// Changes the opacity of the band
+    ///
+    /// **Note** By default, the band is mostly transparent (`opacity = 0.3`)
+    ///
+    /// # Panics
+    ///
+    /// Panics if `opacity` is outside the range `[0, 1]`
+    pub fn opacity(&mut self, opacity: f64) -> &mut Properties {
+        assert!((0. ..=1.).contains(&opacity));
+
+        self.opacity = opacity;
+        self
+    }
+
+    fn axes_script(&self) -> String {
+        if let Some(axes) = self.axes {
+            format!("axes {} ", axes.display())
+        } else {
+            String::new()
+        }
+    }
+
+    fn line_style_script(&self) -> String {
+        match self.line_style {
+            Some(ls) => format!("ls {} ", ls.number()),
+            None => String::new(),
+        }
+    }
+
+    fn color_script(&self) -> String {
+        match self.color {
+            Some(color) => format!("lc rgb '{}' ", color.display()),
+            None => String::new(),
+        }
+    }
+}
+
+impl Default for Properties {
+    fn default() -> Properties {
+        Properties {
+            axes: None,
+            color: None,
+            label: None,
+            line_style: None,
+            line_type: None,
+            linewidth: None,
+            opacity: 0.3,
+        }
+    }
+}
+
+/// The band, which doesn't get an entry in the key
+struct Band<'a>(&'a Properties);
+
+impl<'a> Script for Band<'a> {
+    fn needs_color(&self) -> bool {
+        self.0.color.is_none() && self.0.line_style.is_none()
+    }
+
+    fn script(&self) -> String {
+        let properties = self.0;
+
+        let mut script = properties.axes_script();
+        script.push_str("with filledcurves ");
+        script.push_str(&properties.line_style_script());
+        script.push_str(&format!(
+            "fillstyle transparent solid {} noborder ",
+            properties.opacity
+        ));
+        script.push_str(&properties.color_script());
+
+        script
+    }
+}
+
+/// The center line, which carries the entry in the key
+struct Line<'a>(&'a Properties);
+
+impl<'a> Script for Line<'a> {
+    fn label(&self) -> Option<&Text> {
+        self.0.label.as_ref()
+    }
+
+    fn needs_line_width(&self) -> bool {
+        self.0.linewidth.is_none() && self.0.line_style.is_none()
+    }
+
+    fn script(&self) -> String {
+        let properties = self.0;
+
+        let mut script = properties.axes_script();
+        script.push_str("with lines ");
+        script.push_str(&properties.line_style_script());
+
+        if let Some(ref lt) = properties.line_type {
+            script.push_str(&format!("dt {} ", lt.display()))
+        }
+
+        if let Some(lw) = properties.linewidth {
+            script.push_str(&format!("lw {} ", lw))
+        }
+
+        script.push_str(&properties.color_script());
+
+        script
+    }
+}
+
+/// A line surrounded by a shaded band, e.g. a mean and its confidence interval
+pub struct ErrorBand<X, Y, L, H> {
+    /// X coordinate of the data points
+    pub x: X,
+    /// Y coordinate of the data points of the center line
+    pub y: Y,
+    /// Y coordinate of the bottom of the band
+    pub low: L,
+    /// Y coordinate of the top of the band
+    pub high: H,
+}
+
+impl<X, Y, L, H> traits::Plot<ErrorBand<X, Y, L, H>> for Figure
+where
+    H: IntoIterator,
+    H::Item: Data,
+    L: IntoIterator,
+    L::Item: Data,
+    X: IntoIterator,
+    X::Item: Data,
+    Y: IntoIterator,
+    Y::Item: Data,
+{
+    type Properties = Properties;
+
+    fn plot<F>(&mut self, band: ErrorBand<X, Y, L, H>, configure: F) -> &mut Figure
+    where
+        F: FnOnce(&mut Properties) -> &mut Properties,
+    {
+        let ErrorBand { x, y, low, high } = band;
+
+        let mut props = Default::default();
+        configure(&mut props);
+
+        let axes = props.axes.unwrap_or(crate::Axes::BottomXLeftY);
+        let (x_factor, y_factor) = crate::scale_factor(&self.axes, axes);
+
+        let (mut xs, mut ys, mut lows, mut highs) = (vec![], vec![], vec![], vec![]);
+        for (x, y, low, high) in izip!(x, y, low, high) {
+            xs.push(x.f64());
+            ys.push(y.f64());
+            lows.push(low.f64());
+            highs.push(high.f64());
+        }
+
+        let band = Matrix::from_columns(&[&xs, &lows, &highs], &[x_factor, y_factor, y_factor]);
+        let line = Matrix::from_columns(&[&xs, &ys], &[x_factor, y_factor]);
+
+        self.push(
+            Plot::new(
+                axes,
+                &[Coordinate::X, Coordinate::Y, Coordinate::Y],
+                band,
+                &Band(&props),
+            ),
+            None,
+        );
+        self.push(
+            Plot::new(axes, &[Coordinate::X, Coordinate::Y], line, &Line(&props))
+                .sharing_color(props.color.is_none()),
+            None,
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::prelude::*;
+
+    #[test]
+    fn band_and_line() {
+        let mut figure = Figure::new();
+        figure.plot(
+            ErrorBand {
+                x: &[1., 2., 3.],
+                y: &[2., 3., 4.],
+                low: &[1., 2., 3.],
+                high: &[3., 5., 6.],
+            },
+            |eb| eb.label("mean").line_width(2.).opacity(0.5),
+        );
+        let script = figure.script();
+
+        assert!(script.contains("with filledcurves fillstyle transparent solid 0.5 noborder "));
+        assert!(script.contains("with lines lw 2 "));
+        assert!(script.contains("title 'mean'"));
+        assert_eq!(figure.data_range(Axis::BottomX), Some((1., 3.)));
+        assert_eq!(figure.data_range(Axis::LeftY), Some((1., 6.)));
+    }
+
+    #[test]
+    fn band_and_line_share_the_color() {
+        let mut figure = Figure::new();
+        figure.plot(
+            ErrorBand {
+                x: &[1., 2.],
+                y: &[2., 3.],
+                low: &[1., 2.],
+                high: &[3., 4.],
+            },
+            |eb| eb.color(Color::Red).axes(Axes::BottomXRightY),
+        );
+        let script = figure.script();
+
+        assert_eq!(script.matches("lc rgb 'red'").count(), 2);
+        assert_eq!(script.matches("axes x1y2 ").count(), 2);
+        assert_eq!(figure.data_range(Axis::RightY), Some((1., 4.)));
+        assert_eq!(figure.data_range(Axis::LeftY), None);
+    }
+
+    #[test]
+    fn band_and_line_take_the_line_style() {
+        let mut figure = Figure::new();
+        let style = figure.line_style(|ls| ls.color(Color::Red).line_width(2.));
+        figure.plot(
+            ErrorBand {
+                x: &[1., 2.],
+                y: &[2., 3.],
+                low: &[1., 2.],
+                high: &[3., 4.],
+            },
+            |eb| eb.line_style(style).line_type(LineType::Dash),
+        );
+        let script = figure.script();
+
+        assert!(script.contains("with filledcurves ls 1 fillstyle transparent solid 0.3 noborder "));
+        assert!(script.contains("with lines ls 1 dt "));
+        assert!(!script[script.find("plot ").unwrap()..].contains("lc rgb"));
+    }
+}
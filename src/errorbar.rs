@@ -1,25 +1,80 @@
 //! Error bar plots
 
 use itertools::izip;
-use std::borrow::Cow;
 use std::iter::IntoIterator;
 
 use crate::data::Matrix;
 use crate::traits::{self, Data};
-use crate::{Color, Display, ErrorBarDefault, Figure, LineType, Plot, PointType, Script};
+use crate::{
+    Axes, Caps, Color, Column, Coordinate, Display, ErrorBarDefault, Figure, LineStyleId, LineType,
+    Plot, PointType, Script, Text,
+};
 
 /// Properties common to error bar plots
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(feature = "serde", serde(default = "Properties::y_error_bars"))]
 pub struct Properties {
+    axes: Option<Axes>,
+    #[cfg_attr(feature = "serde", serde(deserialize_with = "crate::de::checked"))]
+    caps: Option<Caps>,
     color: Option<Color>,
-    label: Option<Cow<'static, str>>,
-    line_type: LineType,
+    label: Option<Text>,
+    // The line styles of a figure aren't deserialized
+    #[cfg_attr(feature = "serde", serde(skip))]
+    line_style: Option<LineStyleId>,
+    #[cfg_attr(feature = "serde", serde(deserialize_with = "crate::de::checked"))]
+    line_type: Option<LineType>,
+    #[cfg_attr(feature = "serde", serde(deserialize_with = "crate::de::positive"))]
     linewidth: Option<f64>,
+    #[cfg_attr(feature = "serde", serde(deserialize_with = "crate::de::positive"))]
     point_size: Option<f64>,
     point_type: Option<PointType>,
+    #[cfg_attr(feature = "serde", serde(skip))]
     style: Style,
 }
 
 impl Properties {
+    #[cfg(feature = "serde")]
+    fn y_error_bars() -> Properties {
+        ErrorBarDefault::default(Style::YErrorBars)
+    }
+
+    /// Replaces every property but the style, which is picked by the plot
+    #[cfg(feature = "serde")]
+    pub(crate) fn replace(&mut self, properties: Properties) -> &mut Properties {
+        *self = Properties {
+            style: self.style,
+            ..properties
+        };
+        self
+    }
+
+    /// Select the axes to plot against
+    ///
+    /// **Note** By default, the `BottomXLeftY` axes are used
+    pub fn axes(&mut self, axes: Axes) -> &mut Properties {
+        self.axes = Some(axes);
+        self
+    }
+
+    /// Changes the end caps of the error bars
+    ///
+    /// **Note** By default, the caps set with `Figure::caps` are used. gnuplot uses a single cap
+    /// size for all the error bars of a figure, so if several plots set different caps the last
+    /// one wins
+    ///
+    /// # Panics
+    ///
+    /// Panics if the size of `Caps::Size` is a negative value
+    pub fn caps(&mut self, caps: Caps) -> &mut Properties {
+        if let Caps::Size(size) = caps {
+            assert!(size >= 0.);
+        }
+
+        self.caps = Some(caps);
+        self
+    }
+
     /// Changes the color of the error bars
     pub fn color(&mut self, color: Color) -> &mut Properties {
         self.color = Some(color);
@@ -29,17 +84,32 @@ impl Properties {
     /// Sets the legend label
     pub fn label<S>(&mut self, label: S) -> &mut Properties
     where
-        S: Into<Cow<'static, str>>,
+        S: Into<Text>,
     {
         self.label = Some(label.into());
         self
     }
 
+    /// References a line style defined with [`Figure::line_style`], the properties set on the
+    /// plot take precedence over the ones of the style
+    ///
+    /// [`Figure::line_style`]: ../struct.Figure.html#method.line_style
+    pub fn line_style(&mut self, style: LineStyleId) -> &mut Properties {
+        self.line_style = Some(style);
+        self
+    }
+
     /// Change the line type
     ///
-    /// **Note** By default `Solid` lines are used
+    /// **Note** By default `Solid` lines are used, or the line type of the line style
+    ///
+    /// # Panics
+    ///
+    /// Panics if a `Custom` dash pattern is invalid
     pub fn line_type(&mut self, lt: LineType) -> &mut Properties {
-        self.line_type = lt;
+        lt.check();
+
+        self.line_type = Some(lt);
         self
     }
 
@@ -77,9 +147,12 @@ impl Properties {
 impl ErrorBarDefault<Style> for Properties {
     fn default(style: Style) -> Properties {
         Properties {
+            axes: None,
+            caps: None,
             color: None,
             label: None,
-            line_type: LineType::Solid,
+            line_style: None,
+            line_type: None,
             linewidth: None,
             point_type: None,
             point_size: None,
@@ -89,10 +162,34 @@ impl ErrorBarDefault<Style> for Properties {
 }
 
 impl Script for Properties {
+    fn label(&self) -> Option<&Text> {
+        self.label.as_ref()
+    }
+
+    fn needs_color(&self) -> bool {
+        self.color.is_none() && self.line_style.is_none()
+    }
+
+    fn needs_line_width(&self) -> bool {
+        self.linewidth.is_none() && self.line_style.is_none()
+    }
+
     fn script(&self) -> String {
-        let mut script = format!("with {} ", self.style.display());
+        let mut script = if let Some(axes) = self.axes {
+            format!("axes {} ", axes.display())
+        } else {
+            String::new()
+        };
+
+        script.push_str(&format!("with {} ", self.style.display()));
 
-        script.push_str(&format!("lt {} ", self.line_type.display()));
+        if let Some(ls) = self.line_style {
+            script.push_str(&format!("ls {} ", ls.number()))
+        }
+
+        if let Some(ref lt) = self.line_type {
+            script.push_str(&format!("dt {} ", lt.display()))
+        }
 
         if let Some(lw) = self.linewidth {
             script.push_str(&format!("lw {} ", lw))
@@ -110,22 +207,17 @@ impl Script for Properties {
             script.push_str(&format!("ps {} ", ps))
         }
 
-        if let Some(ref label) = self.label {
-            script.push_str("title '");
-            script.push_str(label);
-            script.push('\'')
-        } else {
-            script.push_str("notitle")
-        }
-
         script
     }
 }
 
 #[derive(Clone, Copy)]
 enum Style {
+    BoxXyError,
     XErrorBars,
     XErrorLines,
+    XyErrorBars,
+    XyErrorLines,
     YErrorBars,
     YErrorLines,
 }
@@ -133,14 +225,29 @@ enum Style {
 impl Display<&'static str> for Style {
     fn display(&self) -> &'static str {
         match *self {
+            Style::BoxXyError => "boxxyerror",
             Style::XErrorBars => "xerrorbars",
             Style::XErrorLines => "xerrorlines",
+            Style::XyErrorBars => "xyerrorbars",
+            Style::XyErrorLines => "xyerrorlines",
             Style::YErrorBars => "yerrorbars",
             Style::YErrorLines => "yerrorlines",
         }
     }
 }
 
+impl Style {
+    /// Whether the error bars of this style are horizontal, i.e. the errors are on `x`
+    fn is_horizontal(self) -> bool {
+        matches!(self, Style::XErrorBars | Style::XErrorLines)
+    }
+}
+
+const X_ERROR_COLUMNS: &[Coordinate] =
+    &[Coordinate::X, Coordinate::Y, Coordinate::X, Coordinate::X];
+const Y_ERROR_COLUMNS: &[Coordinate] =
+    &[Coordinate::X, Coordinate::Y, Coordinate::Y, Coordinate::Y];
+
 /// Asymmetric error bar plots
 pub enum ErrorBar<X, Y, L, H> {
     /// Horizontal error bars
@@ -198,6 +305,35 @@ impl<X, Y, L, H> ErrorBar<X, Y, L, H> {
             ErrorBar::YErrorLines { .. } => Style::YErrorLines,
         }
     }
+
+    fn into_data(self) -> (X, Y, L, H) {
+        match self {
+            ErrorBar::XErrorBars {
+                x,
+                y,
+                x_low,
+                x_high,
+            }
+            | ErrorBar::XErrorLines {
+                x,
+                y,
+                x_low,
+                x_high,
+            } => (x, y, x_low, x_high),
+            ErrorBar::YErrorBars {
+                x,
+                y,
+                y_low,
+                y_high,
+            }
+            | ErrorBar::YErrorLines {
+                x,
+                y,
+                y_low,
+                y_high,
+            } => (x, y, y_low, y_high),
+        }
+    }
 }
 
 impl<X, Y, L, H> traits::Plot<ErrorBar<X, Y, L, H>> for Figure
@@ -217,61 +353,583 @@ where
     where
         F: FnOnce(&mut Properties) -> &mut Properties,
     {
-        let (x_factor, y_factor) = crate::scale_factor(&self.axes, crate::Axes::BottomXLeftY);
+        let style = e.style();
+        let mut props = ErrorBarDefault::default(style);
+        configure(&mut props);
+
+        let (x_factor, y_factor) =
+            crate::scale_factor(&self.axes, props.axes.unwrap_or(Axes::BottomXLeftY));
+
+        let (e_factor, columns) = if style.is_horizontal() {
+            (x_factor, X_ERROR_COLUMNS)
+        } else {
+            (y_factor, Y_ERROR_COLUMNS)
+        };
+
+        let (x, y, length, height) = e.into_data();
+        let data = Matrix::new(
+            izip!(x, y, length, height),
+            (x_factor, y_factor, e_factor, e_factor),
+        );
+        push(self, columns, data, &props)
+    }
+}
+
+/// Fast path for large owned or static series: the figure keeps the columns instead of a copy
+impl traits::Plot<ErrorBar<Column, Column, Column, Column>> for Figure {
+    type Properties = Properties;
 
+    fn plot<F>(&mut self, e: ErrorBar<Column, Column, Column, Column>, configure: F) -> &mut Figure
+    where
+        F: FnOnce(&mut Properties) -> &mut Properties,
+    {
         let style = e.style();
-        let (x, y, length, height, e_factor) = match e {
-            ErrorBar::XErrorBars {
+        let mut props = ErrorBarDefault::default(style);
+        configure(&mut props);
+
+        let (x_factor, y_factor) =
+            crate::scale_factor(&self.axes, props.axes.unwrap_or(Axes::BottomXLeftY));
+        let (e_factor, columns) = if style.is_horizontal() {
+            (x_factor, X_ERROR_COLUMNS)
+        } else {
+            (y_factor, Y_ERROR_COLUMNS)
+        };
+
+        let (Column(x), Column(y), Column(length), Column(height)) = e.into_data();
+        let data = Matrix::streamed(vec![
+            (x, x_factor),
+            (y, y_factor),
+            (length, e_factor),
+            (height, e_factor),
+        ]);
+        push(self, columns, data, &props)
+    }
+}
+
+const X_SYMMETRIC_ERROR_COLUMNS: &[Coordinate] =
+    &[Coordinate::X, Coordinate::Y, Coordinate::XDelta];
+const Y_SYMMETRIC_ERROR_COLUMNS: &[Coordinate] =
+    &[Coordinate::X, Coordinate::Y, Coordinate::YDelta];
+
+/// Symmetric error bar plots, where each error bar extends `delta` on both sides of its data point
+pub enum SymmetricErrorBar<X, Y, D> {
+    /// Horizontal error bars
+    XSymmetricErrorBars {
+        /// X coordinate of the data points
+        x: X,
+        /// Y coordinate of the data points
+        y: Y,
+        /// Half the length of the error bar
+        x_delta: D,
+    },
+    /// Horizontal error bars, where each point is joined by a line
+    XSymmetricErrorLines {
+        /// X coordinate of the data points
+        x: X,
+        /// Y coordinate of the data points
+        y: Y,
+        /// Half the length of the error bar
+        x_delta: D,
+    },
+    /// Vertical error bars
+    YSymmetricErrorBars {
+        /// X coordinate of the data points
+        x: X,
+        /// Y coordinate of the data points
+        y: Y,
+        /// Half the height of the error bar
+        y_delta: D,
+    },
+    /// Vertical error bars, where each point is joined by a line
+    YSymmetricErrorLines {
+        /// X coordinate of the data points
+        x: X,
+        /// Y coordinate of the data points
+        y: Y,
+        /// Half the height of the error bar
+        y_delta: D,
+    },
+}
+
+impl<X, Y, D> SymmetricErrorBar<X, Y, D> {
+    fn style(&self) -> Style {
+        match *self {
+            SymmetricErrorBar::XSymmetricErrorBars { .. } => Style::XErrorBars,
+            SymmetricErrorBar::XSymmetricErrorLines { .. } => Style::XErrorLines,
+            SymmetricErrorBar::YSymmetricErrorBars { .. } => Style::YErrorBars,
+            SymmetricErrorBar::YSymmetricErrorLines { .. } => Style::YErrorLines,
+        }
+    }
+
+    fn into_data(self) -> (X, Y, D) {
+        match self {
+            SymmetricErrorBar::XSymmetricErrorBars { x, y, x_delta }
+            | SymmetricErrorBar::XSymmetricErrorLines { x, y, x_delta } => (x, y, x_delta),
+            SymmetricErrorBar::YSymmetricErrorBars { x, y, y_delta }
+            | SymmetricErrorBar::YSymmetricErrorLines { x, y, y_delta } => (x, y, y_delta),
+        }
+    }
+}
+
+impl<X, Y, D> traits::Plot<SymmetricErrorBar<X, Y, D>> for Figure
+where
+    D: IntoIterator,
+    D::Item: Data,
+    X: IntoIterator,
+    X::Item: Data,
+    Y: IntoIterator,
+    Y::Item: Data,
+{
+    type Properties = Properties;
+
+    fn plot<F>(&mut self, e: SymmetricErrorBar<X, Y, D>, configure: F) -> &mut Figure
+    where
+        F: FnOnce(&mut Properties) -> &mut Properties,
+    {
+        let style = e.style();
+        let mut props = ErrorBarDefault::default(style);
+        configure(&mut props);
+
+        let (x_factor, y_factor) =
+            crate::scale_factor(&self.axes, props.axes.unwrap_or(Axes::BottomXLeftY));
+
+        let (e_factor, columns) = if style.is_horizontal() {
+            (x_factor, X_SYMMETRIC_ERROR_COLUMNS)
+        } else {
+            (y_factor, Y_SYMMETRIC_ERROR_COLUMNS)
+        };
+
+        let (x, y, delta) = e.into_data();
+        let data = Matrix::new(izip!(x, y, delta), (x_factor, y_factor, e_factor));
+        push(self, columns, data, &props)
+    }
+}
+
+/// Fast path for large owned or static series: the figure keeps the columns instead of a copy
+impl traits::Plot<SymmetricErrorBar<Column, Column, Column>> for Figure {
+    type Properties = Properties;
+
+    fn plot<F>(&mut self, e: SymmetricErrorBar<Column, Column, Column>, configure: F) -> &mut Figure
+    where
+        F: FnOnce(&mut Properties) -> &mut Properties,
+    {
+        let style = e.style();
+        let mut props = ErrorBarDefault::default(style);
+        configure(&mut props);
+
+        let (x_factor, y_factor) =
+            crate::scale_factor(&self.axes, props.axes.unwrap_or(Axes::BottomXLeftY));
+        let (e_factor, columns) = if style.is_horizontal() {
+            (x_factor, X_SYMMETRIC_ERROR_COLUMNS)
+        } else {
+            (y_factor, Y_SYMMETRIC_ERROR_COLUMNS)
+        };
+
+        let (Column(x), Column(y), Column(delta)) = e.into_data();
+        let data = Matrix::streamed(vec![(x, x_factor), (y, y_factor), (delta, e_factor)]);
+        push(self, columns, data, &props)
+    }
+}
+
+const XY_ERROR_COLUMNS: &[Coordinate] = &[
+    Coordinate::X,
+    Coordinate::Y,
+    Coordinate::X,
+    Coordinate::X,
+    Coordinate::Y,
+    Coordinate::Y,
+];
+
+/// Error bar plots with both horizontal and vertical error bars
+pub enum XyErrorBar<X, Y, XL, XH, YL, YH> {
+    /// Horizontal and vertical error bars
+    XyErrorBars {
+        /// X coordinate of the data points
+        x: X,
+        /// Y coordinate of the data points
+        y: Y,
+        /// X coordinate of the left end of the horizontal error bar
+        x_low: XL,
+        /// X coordinate of the right end of the horizontal error bar
+        x_high: XH,
+        /// Y coordinate of the bottom of the vertical error bar
+        y_low: YL,
+        /// Y coordinate of the top of the vertical error bar
+        y_high: YH,
+    },
+    /// Horizontal and vertical error bars, where each point is joined by a line
+    XyErrorLines {
+        /// X coordinate of the data points
+        x: X,
+        /// Y coordinate of the data points
+        y: Y,
+        /// X coordinate of the left end of the horizontal error bar
+        x_low: XL,
+        /// X coordinate of the right end of the horizontal error bar
+        x_high: XH,
+        /// Y coordinate of the bottom of the vertical error bar
+        y_low: YL,
+        /// Y coordinate of the top of the vertical error bar
+        y_high: YH,
+    },
+    /// A rectangle that spans the error on both coordinates
+    BoxXyError {
+        /// X coordinate of the data points
+        x: X,
+        /// Y coordinate of the data points
+        y: Y,
+        /// X coordinate of the left side of the box
+        x_low: XL,
+        /// X coordinate of the right side of the box
+        x_high: XH,
+        /// Y coordinate of the bottom side of the box
+        y_low: YL,
+        /// Y coordinate of the top side of the box
+        y_high: YH,
+    },
+}
+
+impl<X, Y, XL, XH, YL, YH> XyErrorBar<X, Y, XL, XH, YL, YH> {
+    fn style(&self) -> Style {
+        match *self {
+            XyErrorBar::XyErrorBars { .. } => Style::XyErrorBars,
+            XyErrorBar::XyErrorLines { .. } => Style::XyErrorLines,
+            XyErrorBar::BoxXyError { .. } => Style::BoxXyError,
+        }
+    }
+
+    fn into_data(self) -> (X, Y, XL, XH, YL, YH) {
+        match self {
+            XyErrorBar::XyErrorBars {
                 x,
                 y,
                 x_low,
                 x_high,
+                y_low,
+                y_high,
             }
-            | ErrorBar::XErrorLines {
+            | XyErrorBar::XyErrorLines {
                 x,
                 y,
                 x_low,
                 x_high,
-            } => (x, y, x_low, x_high, x_factor),
-            ErrorBar::YErrorBars {
-                x,
-                y,
                 y_low,
                 y_high,
             }
-            | ErrorBar::YErrorLines {
+            | XyErrorBar::BoxXyError {
                 x,
                 y,
+                x_low,
+                x_high,
                 y_low,
                 y_high,
-            } => (x, y, y_low, y_high, y_factor),
-        };
+            } => (x, y, x_low, x_high, y_low, y_high),
+        }
+    }
+}
+
+impl<X, Y, XL, XH, YL, YH> traits::Plot<XyErrorBar<X, Y, XL, XH, YL, YH>> for Figure
+where
+    X: IntoIterator,
+    X::Item: Data,
+    XH: IntoIterator,
+    XH::Item: Data,
+    XL: IntoIterator,
+    XL::Item: Data,
+    Y: IntoIterator,
+    Y::Item: Data,
+    YH: IntoIterator,
+    YH::Item: Data,
+    YL: IntoIterator,
+    YL::Item: Data,
+{
+    type Properties = Properties;
+
+    fn plot<F>(&mut self, e: XyErrorBar<X, Y, XL, XH, YL, YH>, configure: F) -> &mut Figure
+    where
+        F: FnOnce(&mut Properties) -> &mut Properties,
+    {
+        let style = e.style();
+        let mut props = ErrorBarDefault::default(style);
+        configure(&mut props);
+
+        let (x_factor, y_factor) =
+            crate::scale_factor(&self.axes, props.axes.unwrap_or(Axes::BottomXLeftY));
+
+        let (x, y, x_low, x_high, y_low, y_high) = e.into_data();
         let data = Matrix::new(
-            izip!(x, y, length, height),
-            (x_factor, y_factor, e_factor, e_factor),
+            izip!(x, y, x_low, x_high, y_low, y_high),
+            (x_factor, y_factor, x_factor, x_factor, y_factor, y_factor),
         );
-        self.plots.push(Plot::new(
-            data,
-            configure(&mut ErrorBarDefault::default(style)),
-        ));
-        self
+        push(self, XY_ERROR_COLUMNS, data, &props)
+    }
+}
+
+/// Fast path for large owned or static series: the figure keeps the columns instead of a copy
+impl traits::Plot<XyErrorBar<Column, Column, Column, Column, Column, Column>> for Figure {
+    type Properties = Properties;
+
+    fn plot<F>(
+        &mut self,
+        e: XyErrorBar<Column, Column, Column, Column, Column, Column>,
+        configure: F,
+    ) -> &mut Figure
+    where
+        F: FnOnce(&mut Properties) -> &mut Properties,
+    {
+        let style = e.style();
+        let mut props = ErrorBarDefault::default(style);
+        configure(&mut props);
+
+        let (x_factor, y_factor) =
+            crate::scale_factor(&self.axes, props.axes.unwrap_or(Axes::BottomXLeftY));
+
+        let (Column(x), Column(y), Column(x_low), Column(x_high), Column(y_low), Column(y_high)) =
+            e.into_data();
+        let data = Matrix::streamed(vec![
+            (x, x_factor),
+            (y, y_factor),
+            (x_low, x_factor),
+            (x_high, x_factor),
+            (y_low, y_factor),
+            (y_high, y_factor),
+        ]);
+        push(self, XY_ERROR_COLUMNS, data, &props)
     }
 }
 
-// TODO XY error bar
-// pub struct XyErrorBar<X, Y, XL, XH, YL, YH> {
-// x: X,
-// y: Y,
-// x_low: XL,
-// x_high: XH,
-// y_low: YL,
-// y_high: YH,
-// }
-
-// TODO Symmetric error bars
-// pub enum SymmetricErrorBar {
-// XSymmetricErrorBar { x: X, y: Y, x_delta: D },
-// XSymmetricErrorLines { x: X, y: Y, x_delta: D },
-// YSymmetricErrorBar { x: X, y: Y, y_delta: D },
-// YSymmetricErrorLines { x: X, y: Y, y_delta: D },
-// }
+fn push<'a>(
+    figure: &'a mut Figure,
+    columns: &'static [Coordinate],
+    data: Matrix,
+    props: &Properties,
+) -> &'a mut Figure {
+    let mut plot = Plot::new(
+        props.axes.unwrap_or(Axes::BottomXLeftY),
+        columns,
+        data,
+        props,
+    );
+    plot.caps = props.caps;
+
+    figure.push(plot, None)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::prelude::*;
+
+    #[test]
+    fn columns_and_iterators_produce_the_same_plot() {
+        let (x, y, low, high) = (vec![1., 2.], vec![2., 3.], vec![1.5, 2.5], vec![3., 4.]);
+
+        let mut iterators = Figure::new();
+        iterators
+            .plot(
+                YErrorBars {
+                    x: &x,
+                    y: &y,
+                    y_low: &low,
+                    y_high: &high,
+                },
+                |e| e,
+            )
+            .plot(
+                XSymmetricErrorBars {
+                    x: &x,
+                    y: &y,
+                    x_delta: &low,
+                },
+                |e| e,
+            )
+            .plot(
+                BoxXyError {
+                    x: &x,
+                    y: &y,
+                    x_low: &low,
+                    x_high: &high,
+                    y_low: &low,
+                    y_high: &high,
+                },
+                |e| e,
+            );
+        let mut columns = Figure::new();
+        columns
+            .plot(
+                YErrorBars {
+                    x: Column::from(x.clone()),
+                    y: Column::from(y.clone()),
+                    y_low: Column::from(low.clone()),
+                    y_high: Column::from(high.clone()),
+                },
+                |e| e,
+            )
+            .plot(
+                XSymmetricErrorBars {
+                    x: Column::from(x.clone()),
+                    y: Column::from(y.clone()),
+                    x_delta: Column::from(low.clone()),
+                },
+                |e| e,
+            )
+            .plot(
+                BoxXyError {
+                    x: Column::from(x),
+                    y: Column::from(y),
+                    x_low: Column::from(low.clone()),
+                    x_high: Column::from(high.clone()),
+                    y_low: Column::from(low),
+                    y_high: Column::from(high),
+                },
+                |e| e,
+            );
+
+        let (mut expected, mut actual) = (Vec::new(), Vec::new());
+        iterators.dump(&mut expected).unwrap();
+        columns.dump(&mut actual).unwrap();
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn plots_override_the_caps_of_the_figure() {
+        let plot = |figure: &mut Figure, caps: Option<Caps>| {
+            figure.plot(
+                YErrorBars {
+                    x: &[1.],
+                    y: &[2.],
+                    y_low: &[1.],
+                    y_high: &[3.],
+                },
+                |e| match caps {
+                    Some(caps) => e.caps(caps),
+                    None => e,
+                },
+            );
+        };
+
+        let mut figure = Figure::new();
+        figure.caps(Caps::Small);
+        plot(&mut figure, None);
+        assert!(figure.script().contains("\nset bars small\n"));
+
+        plot(&mut figure, Some(Caps::Size(2.)));
+        assert!(figure.script().contains("\nset bars 2\n"));
+    }
+
+    #[test]
+    fn symmetric_error_bars() {
+        let mut figure = Figure::new();
+        figure
+            .plot(
+                XSymmetricErrorBars {
+                    x: &[1., 2.],
+                    y: &[1., 2.],
+                    x_delta: &[0.5, -1.],
+                },
+                |eb| eb,
+            )
+            .plot(
+                YSymmetricErrorLines {
+                    x: &[1., 2.],
+                    y: &[5., 6.],
+                    y_delta: &[2., 0.5],
+                },
+                |eb| eb.axes(Axes::BottomXRightY),
+            );
+        let script = figure.script();
+
+        assert!(script.contains("using 1:2:3 with xerrorbars "));
+        assert!(script.contains("using 1:2:3 axes x1y2 with yerrorlines "));
+        assert_eq!(Some((0.5, 3.)), figure.data_range(Axis::BottomX));
+        assert_eq!(Some((1., 2.)), figure.data_range(Axis::LeftY));
+        assert_eq!(Some((3., 7.)), figure.data_range(Axis::RightY));
+    }
+
+    #[test]
+    fn xy_error_bars() {
+        let mut figure = Figure::new();
+        figure
+            .plot(
+                XyErrorBars {
+                    x: &[1.],
+                    y: &[1.],
+                    x_low: &[0.],
+                    x_high: &[3.],
+                    y_low: &[-1.],
+                    y_high: &[2.],
+                },
+                |eb| eb,
+            )
+            .plot(
+                BoxXyError {
+                    x: &[1.],
+                    y: &[1.],
+                    x_low: &[0.5],
+                    x_high: &[1.5],
+                    y_low: &[0.],
+                    y_high: &[4.],
+                },
+                |eb| eb.color(Color::Red),
+            );
+        let script = figure.script();
+
+        assert!(script.contains("using 1:2:3:4:5:6 with xyerrorbars "));
+        assert!(script.contains("using 1:2:3:4:5:6 with boxxyerror "));
+        assert!(script.contains("lc rgb 'red' "));
+        assert_eq!(Some((0., 3.)), figure.data_range(Axis::BottomX));
+        assert_eq!(Some((-1., 4.)), figure.data_range(Axis::LeftY));
+    }
+
+    #[test]
+    fn error_bar_caps() {
+        let mut figure = Figure::new();
+        assert!(figure.script().contains("\nunset bars\n"));
+
+        figure
+            .caps(Caps::Small)
+            .caps_layer(Layer::Back)
+            .plot(
+                YErrorBars {
+                    x: &[1.],
+                    y: &[1.],
+                    y_low: &[0.5],
+                    y_high: &[1.5],
+                },
+                |eb| eb,
+            )
+            .plot(
+                Candlesticks {
+                    x: &[1.],
+                    whisker_min: &[0.],
+                    box_min: &[0.5],
+                    box_high: &[1.5],
+                    whisker_high: &[2.],
+                },
+                |cs| cs.caps(Caps::Large),
+            );
+
+        let script = figure.script();
+        assert!(script.contains("\nset bars small back\n"));
+        assert!(script.contains("with candlesticks whiskerbars 1 "));
+    }
+
+    #[test]
+    fn error_bars_on_secondary_axes_are_scaled() {
+        let mut figure = Figure::new();
+        figure
+            .configure_axis(Axis::RightY, |a| a.scale_factor(2.))
+            .plot(
+                YErrorBars {
+                    x: &[1.],
+                    y: &[1.],
+                    y_low: &[0.5],
+                    y_high: &[1.5],
+                },
+                |eb| eb.axes(Axes::BottomXRightY),
+            );
+
+        assert!(figure.script().contains("axes x1y2 with yerrorbars "));
+        assert_eq!(Some((1., 3.)), figure.data_range(Axis::RightY));
+        assert_eq!(None, figure.data_range(Axis::LeftY));
+    }
+}
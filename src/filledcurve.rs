@@ -1,19 +1,30 @@
 //! Filled curve plots
 
 use itertools::izip;
-use std::borrow::Cow;
 use std::iter::IntoIterator;
 
 use crate::data::Matrix;
 use crate::traits::{self, Data};
-use crate::{Axes, Color, Default, Display, Figure, Plot, Script};
+use crate::{Axes, Color, Column, Coordinate, Default, Display, Figure, Plot, Script, Text};
+
+/// Number of fill patterns of gnuplot
+const PATTERNS: usize = 8;
 
 /// Properties common to filled curve plots
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(feature = "serde", serde(default = "Default::default"))]
 pub struct Properties {
     axes: Option<Axes>,
+    border: bool,
+    border_color: Option<Color>,
+    #[cfg_attr(feature = "serde", serde(deserialize_with = "crate::de::positive"))]
+    border_width: Option<f64>,
     color: Option<Color>,
-    label: Option<Cow<'static, str>>,
+    label: Option<Text>,
+    #[cfg_attr(feature = "serde", serde(deserialize_with = "crate::de::fraction"))]
     opacity: Option<f64>,
+    #[cfg_attr(feature = "serde", serde(deserialize_with = "deserialize_pattern"))]
+    pattern: Option<usize>,
 }
 
 impl Properties {
@@ -25,6 +36,37 @@ impl Properties {
         self
     }
 
+    /// Shows or hides the border around the filled area
+    ///
+    /// **Note** By default, the border is hidden
+    pub fn border(&mut self, show: bool) -> &mut Properties {
+        self.border = show;
+        self
+    }
+
+    /// Changes the color of the border
+    ///
+    /// **Note** By default, the border has the fill color. The border is only drawn if it's shown
+    /// with `border(true)`
+    pub fn border_color(&mut self, color: Color) -> &mut Properties {
+        self.border_color = Some(color);
+        self
+    }
+
+    /// Changes the width of the border
+    ///
+    /// **Note** The border is only drawn if it's shown with `border(true)`
+    ///
+    /// # Panics
+    ///
+    /// Panics if `width` is a non-positive value
+    pub fn border_width(&mut self, width: f64) -> &mut Properties {
+        assert!(width > 0.);
+
+        self.border_width = Some(width);
+        self
+    }
+
     /// Sets the fill color
     pub fn color(&mut self, color: Color) -> &mut Properties {
         self.color = Some(color);
@@ -34,7 +76,7 @@ impl Properties {
     /// Sets the legend label
     pub fn label<S>(&mut self, label: S) -> &mut Properties
     where
-        S: Into<Cow<'static, str>>,
+        S: Into<Text>,
     {
         self.label = Some(label.into());
         self
@@ -48,50 +90,132 @@ impl Properties {
     ///
     /// Panics if `opacity` is outside the range `[0, 1]`
     pub fn opacity(&mut self, opacity: f64) -> &mut Properties {
+        assert!((0. ..=1.).contains(&opacity));
+
         self.opacity = Some(opacity);
         self
     }
+
+    /// Fills the area with the hatch pattern number `n` of gnuplot, instead of a solid color
+    ///
+    /// **Note** The opacity doesn't apply to patterns
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is greater than 7
+    pub fn pattern(&mut self, n: usize) -> &mut Properties {
+        assert!(n < PATTERNS);
+
+        self.pattern = Some(n);
+        self
+    }
+}
+
+/// Deserializes the number of a hatch pattern, which must be one of gnuplot's patterns
+#[cfg(feature = "serde")]
+fn deserialize_pattern<'de, D>(deserializer: D) -> Result<Option<usize>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    crate::de::number(
+        deserializer,
+        |n| n < PATTERNS as f64,
+        "a pattern number below 8",
+    )
 }
 
 impl Default for Properties {
     fn default() -> Properties {
         Properties {
             axes: None,
+            border: false,
+            border_color: None,
+            border_width: None,
             color: None,
             label: None,
             opacity: None,
+            pattern: None,
         }
     }
 }
 
-impl Script for Properties {
+/// Region that gets filled
+#[derive(Clone, Copy)]
+pub(crate) enum Mode {
+    /// Between the two curves
+    Between,
+    /// Between the curve and the horizontal line at the given (scaled) value, where the curve
+    /// is above the line
+    Above(f64),
+    /// Between the curve and the horizontal line at the given (scaled) value, where the curve
+    /// is below the line
+    Below(f64),
+    /// Inside the polygon formed by the curve
+    Closed,
+    /// Between the curve and the x axis
+    ToXAxis,
+    /// Between the curve and the y axis
+    ToYAxis,
+}
+
+impl Script for (Mode, &Properties) {
+    fn label(&self) -> Option<&Text> {
+        self.1.label.as_ref()
+    }
+
+    fn needs_color(&self) -> bool {
+        self.1.color.is_none()
+    }
+
     fn script(&self) -> String {
-        let mut script = if let Some(axes) = self.axes {
+        let (mode, properties) = *self;
+
+        let mut script = if let Some(axes) = properties.axes {
             format!("axes {} ", axes.display())
         } else {
             String::new()
         };
         script.push_str("with filledcurves ");
 
+        let (x_axis, y_axis) = match properties.axes.unwrap_or(Axes::BottomXLeftY) {
+            Axes::BottomXLeftY => ("x1", "y1"),
+            Axes::BottomXRightY => ("x1", "y2"),
+            Axes::TopXLeftY => ("x2", "y1"),
+            Axes::TopXRightY => ("x2", "y2"),
+        };
+        match mode {
+            Mode::Between => {}
+            Mode::Above(threshold) => script.push_str(&format!("above {}={} ", y_axis, threshold)),
+            Mode::Below(threshold) => script.push_str(&format!("below {}={} ", y_axis, threshold)),
+            Mode::Closed => script.push_str("closed "),
+            Mode::ToXAxis => script.push_str(&format!("{} ", x_axis)),
+            Mode::ToYAxis => script.push_str(&format!("{} ", y_axis)),
+        }
+
         script.push_str("fillstyle ");
 
-        if let Some(opacity) = self.opacity {
-            script.push_str(&format!("solid {} ", opacity))
+        if let Some(n) = properties.pattern {
+            script.push_str(&format!("pattern {} ", n))
+        } else if let Some(opacity) = properties.opacity {
+            script.push_str(&format!("transparent solid {} ", opacity))
         }
 
-        // TODO border shoulde be configurable
-        script.push_str("noborder ");
+        if properties.border {
+            script.push_str("border ");
 
-        if let Some(color) = self.color {
-            script.push_str(&format!("lc rgb '{}' ", color.display()));
-        }
+            if let Some(color) = properties.border_color {
+                script.push_str(&format!("lc rgb '{}' ", color.display()))
+            }
 
-        if let Some(ref label) = self.label {
-            script.push_str("title '");
-            script.push_str(label);
-            script.push('\'')
+            if let Some(width) = properties.border_width {
+                script.push_str(&format!("lw {} ", width))
+            }
         } else {
-            script.push_str("notitle")
+            script.push_str("noborder ");
+        }
+
+        if let Some(color) = properties.color {
+            script.push_str(&format!("lc rgb '{}' ", color.display()));
         }
 
         script
@@ -128,11 +252,261 @@ where
         let mut props = Default::default();
         configure(&mut props);
 
-        let (x_factor, y_factor) =
-            crate::scale_factor(&self.axes, props.axes.unwrap_or(crate::Axes::BottomXLeftY));
+        let axes = props.axes.unwrap_or(crate::Axes::BottomXLeftY);
+        let (x_factor, y_factor) = crate::scale_factor(&self.axes, axes);
 
         let data = Matrix::new(izip!(x, y1, y2), (x_factor, y_factor, y_factor));
-        self.plots.push(Plot::new(data, &props));
-        self
+        self.push(
+            Plot::new(
+                axes,
+                &[Coordinate::X, Coordinate::Y, Coordinate::Y],
+                data,
+                &(Mode::Between, &props),
+            ),
+            None,
+        )
+    }
+}
+
+/// Fast path for large owned or static series: the figure keeps the columns instead of a copy
+impl traits::Plot<FilledCurve<Column, Column, Column>> for Figure {
+    type Properties = Properties;
+
+    fn plot<F>(&mut self, fc: FilledCurve<Column, Column, Column>, configure: F) -> &mut Figure
+    where
+        F: FnOnce(&mut Properties) -> &mut Properties,
+    {
+        let FilledCurve {
+            x: Column(x),
+            y1: Column(y1),
+            y2: Column(y2),
+        } = fc;
+
+        let mut props = Default::default();
+        configure(&mut props);
+
+        let axes = props.axes.unwrap_or(crate::Axes::BottomXLeftY);
+        let (x_factor, y_factor) = crate::scale_factor(&self.axes, axes);
+
+        let data = Matrix::streamed(vec![(x, x_factor), (y1, y_factor), (y2, y_factor)]);
+        self.push(
+            Plot::new(
+                axes,
+                &[Coordinate::X, Coordinate::Y, Coordinate::Y],
+                data,
+                &(Mode::Between, &props),
+            ),
+            None,
+        )
+    }
+}
+
+/// Fills the area delimited by a single curve
+pub enum FilledArea<X, Y> {
+    /// Fills between the curve and the horizontal line `y = threshold`, where the curve is above
+    /// the line
+    Above {
+        /// X coordinate of the data points
+        x: X,
+        /// Y coordinate of the data points
+        y: Y,
+        /// Y coordinate of the horizontal line
+        threshold: f64,
+    },
+    /// Fills between the curve and the horizontal line `y = threshold`, where the curve is below
+    /// the line
+    Below {
+        /// X coordinate of the data points
+        x: X,
+        /// Y coordinate of the data points
+        y: Y,
+        /// Y coordinate of the horizontal line
+        threshold: f64,
+    },
+    /// Fills the polygon formed by the curve, which is closed by joining its last point to its
+    /// first one
+    Closed {
+        /// X coordinate of the data points
+        x: X,
+        /// Y coordinate of the data points
+        y: Y,
+    },
+    /// Fills between the curve and the x axis the plot is drawn against
+    ToXAxis {
+        /// X coordinate of the data points
+        x: X,
+        /// Y coordinate of the data points
+        y: Y,
+    },
+    /// Fills between the curve and the y axis the plot is drawn against
+    ToYAxis {
+        /// X coordinate of the data points
+        x: X,
+        /// Y coordinate of the data points
+        y: Y,
+    },
+}
+
+impl<X, Y> traits::Plot<FilledArea<X, Y>> for Figure
+where
+    X: IntoIterator,
+    X::Item: Data,
+    Y: IntoIterator,
+    Y::Item: Data,
+{
+    type Properties = Properties;
+
+    fn plot<F>(&mut self, area: FilledArea<X, Y>, configure: F) -> &mut Figure
+    where
+        F: FnOnce(&mut Properties) -> &mut Properties,
+    {
+        let mut props = Default::default();
+        configure(&mut props);
+
+        let axes = props.axes.unwrap_or(crate::Axes::BottomXLeftY);
+        let (x_factor, y_factor) = crate::scale_factor(&self.axes, axes);
+
+        let (mode, x, y) = area.into_data(y_factor);
+        let data = Matrix::new(izip!(x, y), (x_factor, y_factor));
+        self.push(
+            Plot::new(axes, &[Coordinate::X, Coordinate::Y], data, &(mode, &props)),
+            None,
+        )
+    }
+}
+
+/// Fast path for large owned or static series: the figure keeps the columns instead of a copy
+impl traits::Plot<FilledArea<Column, Column>> for Figure {
+    type Properties = Properties;
+
+    fn plot<F>(&mut self, area: FilledArea<Column, Column>, configure: F) -> &mut Figure
+    where
+        F: FnOnce(&mut Properties) -> &mut Properties,
+    {
+        let mut props = Default::default();
+        configure(&mut props);
+
+        let axes = props.axes.unwrap_or(crate::Axes::BottomXLeftY);
+        let (x_factor, y_factor) = crate::scale_factor(&self.axes, axes);
+
+        let (mode, Column(x), Column(y)) = area.into_data(y_factor);
+        let data = Matrix::streamed(vec![(x, x_factor), (y, y_factor)]);
+        self.push(
+            Plot::new(axes, &[Coordinate::X, Coordinate::Y], data, &(mode, &props)),
+            None,
+        )
+    }
+}
+
+impl<X, Y> FilledArea<X, Y> {
+    /// Splits the area in its fill mode, whose threshold is scaled by `y_factor`, and its data
+    fn into_data(self, y_factor: f64) -> (Mode, X, Y) {
+        match self {
+            FilledArea::Above { x, y, threshold } => (Mode::Above(threshold * y_factor), x, y),
+            FilledArea::Below { x, y, threshold } => (Mode::Below(threshold * y_factor), x, y),
+            FilledArea::Closed { x, y } => (Mode::Closed, x, y),
+            FilledArea::ToXAxis { x, y } => (Mode::ToXAxis, x, y),
+            FilledArea::ToYAxis { x, y } => (Mode::ToYAxis, x, y),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::prelude::*;
+
+    #[test]
+    fn borders_and_patterns() {
+        let mut figure = Figure::new();
+        figure
+            .plot(
+                FilledCurve {
+                    x: &[1., 2.],
+                    y1: &[0., 0.],
+                    y2: &[1., 2.],
+                },
+                |f| {
+                    f.border(true)
+                        .border_color(Color::Black)
+                        .border_width(2.)
+                        .color(Color::Red)
+                        .pattern(4)
+                },
+            )
+            .plot(
+                FilledArea::Above {
+                    x: &[1., 2.],
+                    y: &[1., 3.],
+                    threshold: 2.,
+                },
+                |f| f.axes(Axes::BottomXRightY).opacity(0.5),
+            );
+        let script = figure.script();
+
+        assert!(script.contains(
+            "with filledcurves fillstyle pattern 4 border lc rgb 'black' lw 2 lc rgb 'red' notitle"
+        ));
+        assert!(script.contains(
+            "axes x1y2 with filledcurves above y2=2 fillstyle transparent solid 0.5 noborder lc rgb"
+        ));
+    }
+
+    #[test]
+    #[should_panic]
+    fn opacity_out_of_range() {
+        Figure::new().plot(
+            FilledArea::Closed {
+                x: &[1., 2.],
+                y: &[1., 2.],
+            },
+            |f| f.opacity(1.5),
+        );
+    }
+
+    #[test]
+    fn columns_and_iterators_produce_the_same_plot() {
+        let (x, y1, y2) = (vec![1., 2.], vec![0., 1.], vec![3., 4.]);
+
+        let mut iterators = Figure::new();
+        iterators
+            .plot(
+                FilledCurve {
+                    x: &x,
+                    y1: &y1,
+                    y2: &y2,
+                },
+                |f| f,
+            )
+            .plot(
+                FilledArea::Above {
+                    x: &x,
+                    y: &y2,
+                    threshold: 2.,
+                },
+                |f| f.axes(Axes::BottomXRightY),
+            );
+        let mut columns = Figure::new();
+        columns
+            .plot(
+                FilledCurve {
+                    x: Column::from(x.clone()),
+                    y1: Column::from(y1),
+                    y2: Column::from(y2.clone()),
+                },
+                |f| f,
+            )
+            .plot(
+                FilledArea::Above {
+                    x: Column::from(x),
+                    y: Column::from(y2),
+                    threshold: 2.,
+                },
+                |f| f.axes(Axes::BottomXRightY),
+            );
+
+        let (mut expected, mut actual) = (Vec::new(), Vec::new());
+        iterators.dump(&mut expected).unwrap();
+        columns.dump(&mut actual).unwrap();
+        assert_eq!(expected, actual);
     }
 }
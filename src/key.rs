@@ -1,8 +1,7 @@
 //! Key (or legend)
 
-use std::borrow::Cow;
-
-use crate::{Default, Display, Script};
+use crate::text::Markup;
+use crate::{Default, Display, Script, Text};
 
 /// Properties of the key.
 ///
@@ -10,6 +9,8 @@ use crate::{Default, Display, Script};
 ///
 /// [`configure_key`]: ../struct.Figure.html#method.configure_key
 #[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(feature = "serde", serde(default = "Default::default"))]
 pub struct KeyProperties {
     boxed: bool,
     hidden: bool,
@@ -17,7 +18,7 @@ pub struct KeyProperties {
     order: Option<Order>,
     position: Option<Position>,
     stacked: Option<Stacked>,
-    title: Option<Cow<'static, str>>,
+    title: Option<Text>,
 }
 
 impl Default for KeyProperties {
@@ -90,22 +91,30 @@ impl KeyProperties {
     /// Set the title
     pub fn title<S>(&mut self, title: S) -> &mut KeyProperties
     where
-        S: Into<Cow<'static, str>>,
+        S: Into<Text>,
     {
         self.title = Some(title.into());
         self
     }
+
+    /// Places the key at `position`, unless a position has been set
+    pub(crate) fn default_position(&mut self, position: Position) {
+        self.position = self.position.or(Some(position));
+    }
 }
 
-impl Script for KeyProperties {
+/// The key script, with its title marked up for the terminal
+impl Script for (&KeyProperties, Markup) {
     fn script(&self) -> String {
-        let mut script = if self.hidden {
+        let &(properties, markup) = self;
+
+        let mut script = if properties.hidden {
             return String::from("set key off\n");
         } else {
             String::from("set key on ")
         };
 
-        match self.position {
+        match properties.position {
             None => {}
             Some(Position::Inside(v, h)) => {
                 script.push_str(&format!("inside {} {} ", v.display(), h.display()))
@@ -115,26 +124,26 @@ impl Script for KeyProperties {
             }
         }
 
-        if let Some(stacked) = self.stacked {
+        if let Some(stacked) = properties.stacked {
             script.push_str(stacked.display());
             script.push(' ');
         }
 
-        if let Some(justification) = self.justification {
+        if let Some(justification) = properties.justification {
             script.push_str(justification.display());
             script.push(' ');
         }
 
-        if let Some(order) = self.order {
+        if let Some(order) = properties.order {
             script.push_str(order.display());
             script.push(' ');
         }
 
-        if let Some(ref title) = self.title {
-            script.push_str(&format!("title '{}' ", title))
+        if let Some(ref title) = properties.title {
+            script.push_str(&format!("title {} ", title.render(markup)))
         }
 
-        if self.boxed {
+        if properties.boxed {
             script.push_str("box ")
         }
 
@@ -145,6 +154,7 @@ impl Script for KeyProperties {
 
 /// Horizontal position of the key
 #[derive(Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub enum Horizontal {
     /// Center of the figure
     Center,
@@ -157,6 +167,7 @@ pub enum Horizontal {
 /// Text justification of the key
 #[allow(missing_docs)]
 #[derive(Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub enum Justification {
     Left,
     Right,
@@ -164,6 +175,7 @@ pub enum Justification {
 
 /// Order of the elements of the key
 #[derive(Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub enum Order {
     /// Sample first, then text
     SampleText,
@@ -174,6 +186,7 @@ pub enum Order {
 /// Position of the key
 // TODO XY position
 #[derive(Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub enum Position {
     /// Inside the area surrounded by the four (BottomX, TopX, LeftY and RightY) axes
     Inside(Vertical, Horizontal),
@@ -184,6 +197,7 @@ pub enum Position {
 /// How the entries of the key are stacked
 #[allow(missing_docs)]
 #[derive(Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub enum Stacked {
     Horizontally,
     Vertically,
@@ -191,6 +205,7 @@ pub enum Stacked {
 
 /// Vertical position of the key
 #[derive(Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub enum Vertical {
     /// Bottom border of the figure
     Bottom,
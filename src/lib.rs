@@ -74,7 +74,7 @@
 //! ```
 //!
 //! - error bars (based on
-//! [Julia plotting tutorial](https://plot.ly/julia/error-bars/#Colored-and-Styled-Error-Bars))
+//!   [Julia plotting tutorial](https://plot.ly/julia/error-bars/#Colored-and-Styled-Error-Bars))
 //!
 //! ![Plot](error_bar.svg)
 //!
@@ -160,7 +160,7 @@
 //! ```
 //!
 //! - Candlesticks (based on
-//! [`candlesticks.dem`](http://gnuplot.sourceforge.net/demo/candlesticks.html))
+//!   [`candlesticks.dem`](http://gnuplot.sourceforge.net/demo/candlesticks.html))
 //!
 //! ![Plot](candlesticks.svg)
 //!
@@ -305,7 +305,7 @@
 //! # }
 //! ```
 //! - Filled curves (based on
-//! [`transparent.dem`](http://gnuplot.sourceforge.net/demo/transparent.html))
+//!   [`transparent.dem`](http://gnuplot.sourceforge.net/demo/transparent.html))
 //!
 //! ![Plot](filled_curve.svg)
 //!
@@ -397,45 +397,91 @@
 use std::borrow::Cow;
 use std::fmt;
 use std::fs::File;
-use std::io;
+use std::io::{self, BufWriter};
 use std::num::ParseIntError;
 use std::path::Path;
 use std::process::{Child, Command};
 use std::str;
 
 use crate::data::Matrix;
+use crate::text::quote;
 
+mod color;
 mod data;
+#[cfg(feature = "serde")]
+mod de;
 mod display;
+mod downsample;
+mod linestyle;
 mod map;
+mod palette;
+mod text;
+mod theme;
 
 pub mod axis;
 pub mod candlestick;
 pub mod curve;
+pub mod errorband;
 pub mod errorbar;
 pub mod filledcurve;
 pub mod key;
 pub mod prelude;
+pub mod scatter;
+#[cfg(feature = "serde")]
+pub mod spec;
+pub mod stackedarea;
 pub mod traits;
 
 use axis::{Axes, Axis, AxisProperties};
 use key::KeyProperties;
 
+pub use crate::color::ParseColorError;
+pub use crate::linestyle::{LineStyle, LineStyleId};
+pub use crate::palette::Palette;
+pub use crate::text::{RichText, Text};
+pub use crate::theme::Theme;
+
 /// Plot container
 #[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(feature = "serde", serde(default = "Figure::new"))]
 pub struct Figure {
+    #[cfg_attr(feature = "serde", serde(deserialize_with = "crate::de::fraction"))]
     alpha: Option<f64>,
     axes: map::axis::Map<axis::AxisProperties>,
+    #[cfg_attr(feature = "serde", serde(deserialize_with = "crate::de::non_negative"))]
     box_width: Option<f64>,
+    #[cfg_attr(feature = "serde", serde(deserialize_with = "crate::de::checked"))]
+    caps: Caps,
+    caps_layer: Option<Layer>,
+    color_map: Option<Palette>,
+    #[cfg_attr(feature = "serde", serde(deserialize_with = "crate::de::checked"))]
+    colors: Option<Vec<Color>>,
+    downsampling: Downsampling,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    dropped: usize,
     font: Option<Cow<'static, str>>,
+    #[cfg_attr(feature = "serde", serde(deserialize_with = "crate::de::non_negative"))]
     font_size: Option<f64>,
     key: Option<KeyProperties>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    line_styles: Vec<LineStyle>,
+    missing_values: MissingValues,
     output: Cow<'static, Path>,
+    // The plots only come from the plot methods (or the plots of a spec), their scripts are
+    // never deserialized
+    #[cfg_attr(feature = "serde", serde(skip))]
     plots: Vec<Plot>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    rejected: usize,
     size: Option<(usize, usize)>,
     terminal: Terminal,
+    theme: Theme,
+    #[cfg_attr(feature = "serde", serde(skip))]
     tics: map::axis::Map<String>,
-    title: Option<Cow<'static, str>>,
+    title: Option<Text>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    warning_handler: fn(&Warning),
 }
 
 impl Figure {
@@ -445,18 +491,39 @@ impl Figure {
             alpha: None,
             axes: map::axis::Map::new(),
             box_width: None,
+            caps: Caps::None,
+            caps_layer: None,
+            color_map: None,
+            colors: None,
+            downsampling: Downsampling::None,
+            dropped: 0,
             font: None,
             font_size: None,
             key: None,
+            line_styles: Vec::new(),
+            missing_values: MissingValues::Break,
             output: Cow::Borrowed(Path::new("output.plot")),
             plots: Vec::new(),
+            rejected: 0,
             size: None,
             terminal: Terminal::Svg,
+            theme: Theme::new(),
             tics: map::axis::Map::new(),
             title: None,
+            warning_handler: |_| {},
         }
     }
 
+    /// Builds a figure from a JSON or TOML spec, where the data of the plots is given inline
+    ///
+    /// See the [`spec`] module for the format of the document
+    ///
+    /// [`spec`]: spec/index.html
+    #[cfg(feature = "serde")]
+    pub fn from_spec(spec: &str, format: spec::Format) -> Result<Figure, spec::SpecError> {
+        spec::Spec::parse(spec, format)?.into_figure(&std::collections::HashMap::new())
+    }
+
     /// Changes the box width of all the box related plots (bars, candlesticks, etc)
     ///
     /// **Note** The default value is 0
@@ -470,6 +537,71 @@ impl Figure {
         self.box_width = Some(width);
         self
     }
+    /// Changes the end caps of the error bars, individual plots can override this setting
+    ///
+    /// **Note** By default, the error bars have no caps. gnuplot uses a single cap size for all
+    /// the error bars of a figure, so the caps of the last plot that sets them win
+    ///
+    /// # Panics
+    ///
+    /// Panics if the size of `Caps::Size` is a negative value
+    pub fn caps(&mut self, caps: Caps) -> &mut Figure {
+        if let Caps::Size(size) = caps {
+            assert!(size >= 0.);
+        }
+
+        self.caps = caps;
+        self
+    }
+    /// Selects whether the error bars and the whiskers of the candlesticks are drawn behind or in
+    /// front of the boxes of the candlesticks
+    ///
+    /// **Note** By default, they are drawn in front
+    pub fn caps_layer(&mut self, layer: Layer) -> &mut Figure {
+        self.caps_layer = Some(layer);
+        self
+    }
+    /// Changes the colors that are given, in insertion order, to the plots that don't set a color,
+    /// e.g. `Palette::OkabeIto.colors()`
+    ///
+    /// **Note** By default, the colors of the theme are used or, if the theme has none, the
+    /// default line colors of gnuplot 5, which are set explicitly so the figures look the same on
+    /// every gnuplot version and terminal
+    ///
+    /// # Panics
+    ///
+    /// Panics if `colors` is empty
+    pub fn color_cycle<I>(&mut self, colors: I) -> &mut Figure
+    where
+        I: IntoIterator<Item = Color>,
+    {
+        let colors = colors.into_iter().collect::<Vec<_>>();
+        assert!(!colors.is_empty());
+
+        self.colors = Some(colors);
+        self
+    }
+    /// Changes the colors that the `ColoredPoints` scatter plots map their values to, from the
+    /// lowest to the highest value
+    ///
+    /// **Note** By default, gnuplot's default color map is used
+    pub fn color_map(&mut self, palette: Palette) -> &mut Figure {
+        self.color_map = Some(palette);
+        self
+    }
+
+    /// Changes how the large series of the plots added afterwards are downsampled, individual
+    /// plots can override this setting
+    ///
+    /// **Note** By default, the series are not downsampled
+    ///
+    /// **Note** The series are downsampled when they're plotted, to the width set with
+    /// `figure_size` at that time. Smoothed curves are never downsampled
+    pub fn downsampling(&mut self, downsampling: Downsampling) -> &mut Figure {
+        self.downsampling = downsampling;
+        self
+    }
+
     /// Changes the font
     pub fn font<S>(&mut self, font: S) -> &mut Figure
     where
@@ -489,6 +621,24 @@ impl Figure {
         self.font_size = Some(size);
         self
     }
+    /// Changes the function that receives the warnings of the figure when it's drawn, dumped or
+    /// saved, e.g. `|warning| eprintln!("warning: {}", warning)` to print them
+    ///
+    /// **Note** By default, the warnings are ignored, [`warnings`] still returns them
+    ///
+    /// [`warnings`]: #method.warnings
+    pub fn on_warning(&mut self, handler: fn(&Warning)) -> &mut Figure {
+        self.warning_handler = handler;
+        self
+    }
+    /// Changes how the plots added afterwards handle missing (`NaN`, infinite or `None`) values,
+    /// individual plots can override this setting
+    ///
+    /// **Note** By default, lines are broken at the missing values
+    pub fn missing_values(&mut self, policy: MissingValues) -> &mut Figure {
+        self.missing_values = policy;
+        self
+    }
     /// Changes the output file
     ///
     /// **Note** The default output file is `output.plot`
@@ -504,6 +654,18 @@ impl Figure {
         self.size = Some((width, height));
         self
     }
+    /// Defines a line style, configured by `configure`, that plots can reference with their
+    /// `line_style` method
+    pub fn line_style<F>(&mut self, configure: F) -> LineStyleId
+    where
+        F: FnOnce(&mut LineStyle) -> &mut LineStyle,
+    {
+        let mut style = Default::default();
+        configure(&mut style);
+
+        self.line_styles.push(style);
+        LineStyleId::new(self.line_styles.len() - 1)
+    }
     /// Changes the output terminal
     ///
     /// **Note** By default, the terminal is set to `Svg`
@@ -511,68 +673,151 @@ impl Figure {
         self.terminal = terminal;
         self
     }
+    /// Applies `theme`, which provides the defaults of the properties that the figure and its
+    /// plots don't set
+    pub fn theme(&mut self, theme: &Theme) -> &mut Figure {
+        self.theme = theme.clone();
+        self
+    }
     /// Sets the title
     pub fn title<S>(&mut self, title: S) -> &mut Figure
     where
-        S: Into<Cow<'static, str>>,
+        S: Into<Text>,
     {
         self.title = Some(title.into());
         self
     }
 
-    fn script(&self) -> Vec<u8> {
+    fn script(&self) -> String {
+        let markup = self.terminal.markup();
         let mut s = String::new();
 
         s.push_str("set encoding utf8\n");
 
-        s.push_str(&format!("set output '{}'\n", self.output.display()));
+        s.push_str(&format!(
+            "set output {}\n",
+            quote(&self.output.display().to_string())
+        ));
 
         if let Some(width) = self.box_width {
             s.push_str(&format!("set boxwidth {}\n", width))
         }
 
+        s.push_str(&self.theme.script());
+
         if let Some(ref title) = self.title {
-            s.push_str(&format!("set title '{}'\n", title))
+            s.push_str(&format!("set title {}\n", title.render(markup)))
         }
 
-        for axis in self.axes.iter() {
-            s.push_str(&axis.script());
+        for (axis, properties) in self.axes.iter() {
+            let data_range = if properties.needs_data_range() {
+                self.data_range(axis)
+            } else {
+                None
+            };
+
+            if let Some(grid) = self.theme.default_gridline(axis) {
+                let mut properties = properties.clone();
+                properties.default_major_grid(grid);
+
+                s.push_str(&(axis, &properties, data_range, markup).script());
+            } else {
+                s.push_str(&(axis, properties, data_range, markup).script());
+            }
+        }
+
+        for &axis in &[Axis::BottomX, Axis::LeftY] {
+            if let Some(grid) = self.theme.default_gridline(axis) {
+                if !self.axes.contains_key(axis) {
+                    s.push_str(&(axis, grid).script());
+                }
+            }
         }
 
         for (_, script) in self.tics.iter() {
             s.push_str(script);
         }
 
-        if let Some(ref key) = self.key {
-            s.push_str(&key.script())
+        let mut key = self.key.clone();
+        if let Some(position) = self.theme.default_key_position() {
+            key.get_or_insert_with(Default::default)
+                .default_position(position);
+        }
+
+        if let Some(ref key) = key {
+            s.push_str(&(key, markup).script())
         }
 
         if let Some(alpha) = self.alpha {
             s.push_str(&format!("set style fill transparent solid {}\n", alpha))
         }
 
-        s.push_str(&format!("set terminal {} dashed", self.terminal.display()));
+        if let Some(palette) = self.color_map {
+            let colors = palette
+                .colors()
+                .iter()
+                .enumerate()
+                .map(|(i, color)| format!("{} '{}'", i, color.display()))
+                .collect::<Vec<_>>();
 
-        if let Some((width, height)) = self.size {
-            s.push_str(&format!(" size {}, {}", width, height))
+            s.push_str(&format!("set palette defined ({})\n", colors.join(", ")));
         }
 
-        if let Some(ref name) = self.font {
-            if let Some(size) = self.font_size {
-                s.push_str(&format!(" font '{},{}'", name, size))
-            } else {
-                s.push_str(&format!(" font '{}'", name))
+        for (i, style) in self.line_styles.iter().enumerate() {
+            s.push_str(&(LineStyleId::new(i), style).script());
+        }
+
+        // The lines are broken at the `NaN`s that replaced the missing values
+        if self.plots.iter().any(|plot| plot.broken) {
+            s.push_str("set datafile missing NaN\n");
+        }
+
+        s.push_str(&format!("set terminal {}", self.terminal.display()));
+
+        if let Some((width, height)) = self.size() {
+            s.push_str(&format!(" size {}", self.terminal.size(width, height)))
+        }
+
+        let font = self.font.as_ref().or(self.theme.default_font());
+        match (font, self.font_size.or(self.theme.default_font_size())) {
+            (Some(name), Some(size)) => {
+                s.push_str(&format!(" font {}", quote(&format!("{},{}", name, size))))
             }
+            (Some(name), None) => s.push_str(&format!(" font {}", quote(name))),
+            (None, Some(size)) => s.push_str(&format!(" font {}", quote(&format!(",{}", size)))),
+            (None, None) => {}
         }
 
-        // TODO This removes the crossbars from the ends of error bars, but should be configurable
-        s.push_str("\nunset bars\n");
+        let caps = self
+            .plots
+            .iter()
+            .rev()
+            .find_map(|plot| plot.caps)
+            .unwrap_or(self.caps);
+        match (caps, self.caps_layer) {
+            (Caps::None, None) => s.push_str("\nunset bars\n"),
+            (caps, None) => s.push_str(&format!("\nset bars {}\n", caps.display())),
+            (caps, Some(layer)) => s.push_str(&format!(
+                "\nset bars {} {}\n",
+                caps.display(),
+                layer.display()
+            )),
+        }
 
+        let mut cycled = 0;
+        let mut color = None;
         let mut is_first_plot = true;
         for plot in &self.plots {
             let data = plot.data();
 
-            if data.bytes().is_empty() {
+            if plot.needs_color {
+                color = Some(self.cycle_color(cycled));
+                cycled += 1;
+            } else if !plot.shares_color {
+                color = None;
+            }
+
+            if data.nrows() == 0 {
                 continue;
             }
 
@@ -600,19 +845,189 @@ impl Figure {
             s.push(' ');
 
             s.push_str(plot.script());
+
+            if plot.needs_line_width {
+                if let Some(lw) = self.theme.default_line_width() {
+                    s.push_str(&format!("lw {} ", lw))
+                }
+            }
+
+            if let Some(color) = color {
+                s.push_str(&format!("lc rgb '{}' ", color.display()))
+            }
+
+            if let Some(ref label) = plot.label {
+                s.push_str(&format!("title {}", label.render(markup)))
+            } else {
+                s.push_str("notitle")
+            }
+        }
+
+        if !self.plots.is_empty() {
+            s.push('\n');
         }
 
-        let mut buffer = s.into_bytes();
-        let mut is_first = true;
+        s
+    }
+
+    /// Writes the script followed by the data of every plot, the data is streamed into `sink`
+    /// without an intermediate buffer
+    fn write<W>(&self, sink: &mut W) -> io::Result<()>
+    where
+        W: io::Write,
+    {
+        self.check()?;
+
+        for warning in self.warnings() {
+            (self.warning_handler)(&warning);
+        }
+
+        sink.write_all(self.script().as_bytes())?;
+
         for plot in &self.plots {
-            if is_first {
-                is_first = false;
-                buffer.push(b'\n');
+            plot.data().write_to(sink)?;
+        }
+
+        Ok(())
+    }
+
+    /// Fails if a plot that rejects missing values contains some
+    fn check(&self) -> io::Result<()> {
+        if self.rejected == 0 {
+            return Ok(());
+        }
+
+        Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!(
+                "{} row(s) of the plot data contain missing values",
+                self.rejected
+            ),
+        ))
+    }
+
+    /// Adds `plot` to the figure, after applying the missing value policy to its data
+    fn push(&mut self, mut plot: Plot, missing_values: Option<MissingValues>) -> &mut Figure {
+        let policy = missing_values.unwrap_or(self.missing_values);
+        let x = plot.columns.iter().position(|c| matches!(c, Coordinate::X));
+
+        let dropped = plot.data.clean(policy, x);
+        plot.broken = policy == MissingValues::Break && dropped != 0;
+
+        if policy == MissingValues::Error {
+            self.rejected += dropped;
+        } else {
+            self.dropped += dropped;
+        }
+        self.plots.push(plot);
+        self
+    }
+
+    /// Color of the `i`-th plot that doesn't set a color
+    fn cycle_color(&self, i: usize) -> Color {
+        let colors = match self.colors {
+            Some(ref colors) => colors,
+            None => self.theme.default_colors().unwrap_or(&DEFAULT_COLORS),
+        };
+
+        colors[i % colors.len()]
+    }
+
+    /// Size of the figure in pixels, if set by the figure or its theme
+    fn size(&self) -> Option<(usize, usize)> {
+        self.size.or(self.theme.default_size())
+    }
+
+    /// Width of the figure in pixels, which is the target of the downsampling
+    fn width(&self) -> usize {
+        self.size().map_or(DEFAULT_WIDTH, |(width, _)| width)
+    }
+
+    /// Data columns plotted against `axis`
+    fn columns(&self, axis: Axis) -> impl Iterator<Item = (&Matrix, usize)> + '_ {
+        self.plots.iter().flat_map(move |plot| {
+            let (x_axis, y_axis) = plot.axes.split();
+
+            plot.columns
+                .iter()
+                .enumerate()
+                .filter(move |&(_, coordinate)| match coordinate {
+                    Coordinate::X => x_axis == axis,
+                    Coordinate::Y => y_axis == axis,
+                    Coordinate::XDelta | Coordinate::YDelta | Coordinate::Value => false,
+                })
+                .map(move |(col, _)| (&plot.data, col))
+        })
+    }
+
+    /// Pairs of (center, delta) columns that extend the data plotted against `axis`
+    fn delta_columns(&self, axis: Axis) -> impl Iterator<Item = (&Matrix, usize, usize)> + '_ {
+        self.plots.iter().flat_map(move |plot| {
+            let (x_axis, y_axis) = plot.axes.split();
+            let position =
+                move |center: fn(&Coordinate) -> bool| plot.columns.iter().position(center);
+
+            plot.columns
+                .iter()
+                .enumerate()
+                .filter_map(move |(col, coordinate)| match coordinate {
+                    Coordinate::XDelta if x_axis == axis => {
+                        position(|c| matches!(c, Coordinate::X)).map(|center| (center, col))
+                    }
+                    Coordinate::YDelta if y_axis == axis => {
+                        position(|c| matches!(c, Coordinate::Y)).map(|center| (center, col))
+                    }
+                    _ => None,
+                })
+                .map(move |(center, delta)| (&plot.data, center, delta))
+        })
+    }
+
+    /// Range covered by the data plotted against `axis`
+    fn data_range(&self, axis: Axis) -> Option<(f64, f64)> {
+        self.columns(axis)
+            .filter_map(|(data, col)| data.column_bounds(col))
+            .chain(
+                self.delta_columns(axis)
+                    .filter_map(|(data, center, delta)| data.delta_bounds(center, delta)),
+            )
+            .fold(None, |range, (low, high)| match range {
+                Some((min, max)) => Some((low.min(min), high.max(max))),
+                None => Some((low, high)),
+            })
+    }
+
+    /// Checks the plotted data for values that gnuplot would silently misrepresent
+    ///
+    /// **Note** gnuplot drops the non-positive values plotted against a logarithmic axis
+    ///
+    /// The warnings are also given to the handler set with [`on_warning`] every time the figure
+    /// is drawn, dumped or saved
+    ///
+    /// [`on_warning`]: #method.on_warning
+    pub fn warnings(&self) -> Vec<Warning> {
+        let mut warnings = Vec::new();
+
+        if self.dropped != 0 {
+            warnings.push(Warning::MissingValues {
+                points: self.dropped,
+            });
+        }
+
+        for (axis, properties) in self.axes.iter() {
+            if properties.is_logarithmic() {
+                let points = self
+                    .columns(axis)
+                    .map(|(data, col)| data.column(col).filter(|&x| x <= 0.).count())
+                    .sum();
+
+                if points != 0 {
+                    warnings.push(Warning::NonPositiveOnLogScale { axis, points });
+                }
             }
-            buffer.extend_from_slice(plot.data().bytes());
         }
 
-        buffer
+        warnings
     }
 
     /// Spawns a drawing child process
@@ -621,6 +1036,7 @@ impl Figure {
     pub fn draw(&mut self) -> io::Result<Child> {
         use std::process::Stdio;
 
+        self.check()?;
         let mut gnuplot = Command::new("gnuplot")
             .stderr(Stdio::piped())
             .stdin(Stdio::piped())
@@ -635,7 +1051,7 @@ impl Figure {
     where
         W: io::Write,
     {
-        sink.write_all(&self.script())?;
+        self.write(sink)?;
         Ok(self)
     }
 
@@ -643,7 +1059,10 @@ impl Figure {
     pub fn save(&self, path: &Path) -> io::Result<&Figure> {
         use std::io::Write;
 
-        File::create(path)?.write_all(&self.script())?;
+        self.check()?;
+        let mut file = BufWriter::new(File::create(path)?);
+        self.write(&mut file)?;
+        file.flush()?;
         Ok(self)
     }
 
@@ -689,8 +1108,14 @@ impl Default for Figure {
 }
 
 /// Color
+///
+/// Colors can also be built from HSL components with [`Color::hsl`], or parsed from strings
+/// like `"#ff6347"` or `"tomato"`.
+///
+/// [`Color::hsl`]: enum.Color.html#method.hsl
 #[allow(missing_docs)]
 #[derive(Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub enum Color {
     Black,
     Blue,
@@ -704,14 +1129,176 @@ pub enum Color {
     Red,
     /// Custom RGB color
     Rgb(u8, u8, u8),
+    /// Custom RGB color with an alpha channel, from `0` (fully transparent) to `255` (opaque)
+    Rgba(u8, u8, u8, u8),
     White,
     Yellow,
 }
 
+impl Color {
+    /// The red, green and blue components of the color
+    pub(crate) fn rgb(self) -> (u8, u8, u8) {
+        match self {
+            Color::Black => (0x00, 0x00, 0x00),
+            Color::Blue => (0x00, 0x00, 0xff),
+            Color::Cyan => (0x00, 0xff, 0xff),
+            Color::DarkViolet => (0x94, 0x00, 0xd3),
+            Color::ForestGreen => (0x22, 0x8b, 0x22),
+            Color::Gold => (0xff, 0xd7, 0x00),
+            Color::Gray => (0xbe, 0xbe, 0xbe),
+            Color::Green => (0x00, 0xff, 0x00),
+            Color::Magenta => (0xff, 0x00, 0xff),
+            Color::Red => (0xff, 0x00, 0x00),
+            Color::Rgb(r, g, b) | Color::Rgba(r, g, b, _) => (r, g, b),
+            Color::White => (0xff, 0xff, 0xff),
+            Color::Yellow => (0xff, 0xff, 0x00),
+        }
+    }
+
+    /// Translates the color into gnuplot's `#AARRGGBB` form, where `AA` is the transparency
+    pub(crate) fn display_with_opacity(self, opacity: f64) -> String {
+        let (r, g, b, a) = self.rgba();
+        let transparency = ((1. - opacity * f64::from(a) / 255.) * 255.).round() as u8;
+
+        format!("#{:02x}{:02x}{:02x}{:02x}", transparency, r, g, b)
+    }
+}
+
+/// Layer in which an element is drawn, relative to the plotted data
+#[derive(Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub enum Layer {
+    /// Behind the data
+    Back,
+    /// In front of the data
+    Front,
+}
+
+/// End caps drawn on the error bars and on the whiskers of the candlesticks
+///
+/// Used by [`Figure::caps`] and by the properties of the error bars and of the candlesticks.
+///
+/// [`Figure::caps`]: struct.Figure.html#method.caps
+#[derive(Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub enum Caps {
+    /// No caps
+    None,
+    /// gnuplot's `small` caps, which have no width on the error bars. On the whiskers, the caps
+    /// span half the box width
+    Small,
+    /// gnuplot's `large` caps, of the default size on the error bars. On the whiskers, the caps
+    /// span the whole box width
+    Large,
+    /// Caps of a custom size. For error bars, the size is relative to the default size; for
+    /// candlesticks, it's a fraction of the box width
+    Size(f64),
+}
+
+impl Caps {
+    fn size(self) -> f64 {
+        match self {
+            Caps::None => 0.,
+            Caps::Small => 0.5,
+            Caps::Large => 1.,
+            Caps::Size(size) => size,
+        }
+    }
+}
+
+/// An owned or `'static` column of data points, which the figure keeps instead of copying it
+///
+/// Plotting iterators requires converting and buffering every data point, which doubles the
+/// memory used by very large series. A `Column` takes ownership of a `Vec<f64>`, or borrows a
+/// `'static` slice, and the figure holds on to it until it's drawn. The columns are then scaled,
+/// interleaved into rows and written to gnuplot a few thousand rows at a time, so only that small
+/// buffer is allocated on top of the data itself.
+///
+/// `Column`s are the column-major input of the plots: every curve, candlestick, error bar, filled
+/// curve, filled area and scatter plot whose series are all `Column`s takes this path. Stacked
+/// areas and error bands compute their data, so they always copy it.
+///
+/// **Note** The figure keeps the columns until it's dropped, so data that's borrowed for a shorter
+/// lifetime (e.g. a slice of benchmark samples) isn't supported: plot it as an iterator, or hand
+/// over a copy with `to_vec()`.
+#[derive(Clone)]
+pub struct Column(Cow<'static, [f64]>);
+
+impl From<Vec<f64>> for Column {
+    fn from(values: Vec<f64>) -> Column {
+        Column(Cow::Owned(values))
+    }
+}
+
+impl From<&'static [f64]> for Column {
+    fn from(values: &'static [f64]) -> Column {
+        Column(Cow::Borrowed(values))
+    }
+}
+
+impl From<Cow<'static, [f64]>> for Column {
+    fn from(values: Cow<'static, [f64]>) -> Column {
+        Column(values)
+    }
+}
+
+/// Width of the figures whose size hasn't been set, matches gnuplot's default
+const DEFAULT_WIDTH: usize = 640;
+
+/// Colors of the plots that don't set one, matches gnuplot 5's default line colors
+const DEFAULT_COLORS: [Color; 8] = [
+    Color::DarkViolet,
+    Color::Rgb(0x00, 0x9e, 0x73),
+    Color::Rgb(0x56, 0xb4, 0xe9),
+    Color::Rgb(0xe6, 0x9f, 0x00),
+    Color::Rgb(0xf0, 0xe4, 0x42),
+    Color::Rgb(0x00, 0x72, 0xb2),
+    Color::Rgb(0xe5, 0x1e, 0x10),
+    Color::Black,
+];
+
+/// Resolution used to convert the figure size into inches
+const PIXELS_PER_INCH: f64 = 72.;
+
+/// Decimation applied to large series, so that they contain about as many points as the figure
+/// has pixels across
+#[derive(Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub enum Downsampling {
+    /// Plot every point
+    None,
+    /// Largest-Triangle-Three-Buckets, keeps one point per pixel preserving the visual shape of
+    /// the series
+    Lttb,
+    /// Keeps the lowest and highest point of each pixel, which preserves every peak
+    MinMax,
+}
+
+/// How plots handle the missing values in their data, i.e. `NaN`s, infinities and `None`s
+#[derive(Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub enum MissingValues {
+    /// Don't draw the points with missing values, which breaks the lines at them
+    Break,
+    /// Fail to draw, dump or save the figure when the data contains missing values
+    Error,
+    /// Linearly interpolate the missing values from the neighboring points, the points that can't
+    /// be interpolated are skipped
+    Interpolate,
+    /// Remove the points with missing values, which joins their neighbors
+    Skip,
+}
+
 /// Line type
+///
+/// The dash patterns are given explicitly to gnuplot, so they look the same on every terminal
 #[allow(missing_docs)]
-#[derive(Clone, Copy)]
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub enum LineType {
+    /// Custom dash pattern, made of up to 4 `(dash, gap)` pairs. The lengths are relative to the
+    /// width of the line
+    Custom(Vec<(f64, f64)>),
     Dash,
     Dot,
     DotDash,
@@ -721,14 +1308,44 @@ pub enum LineType {
     Solid,
 }
 
+impl LineType {
+    /// Checks that the dash pattern can be drawn by gnuplot
+    ///
+    /// # Panics
+    ///
+    /// Panics if a `Custom` pattern is empty, has more than 4 pairs or has negative lengths
+    pub(crate) fn check(&self) {
+        assert!(self.is_valid());
+    }
+
+    /// Whether gnuplot can draw the dash pattern, see `check`
+    pub(crate) fn is_valid(&self) -> bool {
+        match *self {
+            LineType::Custom(ref pattern) => {
+                !pattern.is_empty()
+                    && pattern.len() <= 4
+                    && pattern.iter().all(|&(dash, gap)| dash >= 0. && gap >= 0.)
+            }
+            _ => true,
+        }
+    }
+}
+
 /// Point type
 #[allow(missing_docs)]
 #[derive(Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub enum PointType {
     Circle,
+    Diamond,
     FilledCircle,
+    FilledDiamond,
+    FilledPentagon,
     FilledSquare,
     FilledTriangle,
+    /// Draws the character itself, e.g. `'★'`
+    Glyph(char),
+    Pentagon,
     Plus,
     Square,
     Star,
@@ -737,10 +1354,34 @@ pub enum PointType {
 }
 
 /// Output terminal
-#[allow(missing_docs)]
 #[derive(Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub enum Terminal {
+    /// A PDF with the graphics plus a LaTeX file with the text, to be `\input` in a LaTeX document
+    CairoLatex,
+    /// A PNG image
+    Png,
+    /// An SVG image
     Svg,
+    /// TikZ code, to be `\input` in a LaTeX document
+    ///
+    /// **Note** Requires gnuplot built with Lua support
+    Tikz,
+}
+
+impl Terminal {
+    /// The figure size in the units of the terminal, the LaTeX terminals measure it in inches
+    /// rather than pixels
+    fn size(self, width: usize, height: usize) -> String {
+        match self {
+            Terminal::Png | Terminal::Svg => format!("{}, {}", width, height),
+            Terminal::CairoLatex | Terminal::Tikz => format!(
+                "{}in, {}in",
+                width as f64 / PIXELS_PER_INCH,
+                height as f64 / PIXELS_PER_INCH
+            ),
+        }
+    }
 }
 
 /// Not public version of `std::default::Default`, used to not leak default constructors into the
@@ -772,25 +1413,75 @@ trait ErrorBarDefault<S> {
 trait Script {
     /// Translates some configuration struct into gnuplot code
     fn script(&self) -> String;
+
+    /// The entry of the plot in the key, which is rendered once the terminal is known
+    fn label(&self) -> Option<&Text> {
+        None
+    }
+
+    /// Whether the plot leaves its color to the figure, which picks one from the color cycle
+    fn needs_color(&self) -> bool {
+        false
+    }
+
+    /// Whether the plot leaves its line width to the figure
+    fn needs_line_width(&self) -> bool {
+        false
+    }
+}
+
+/// Coordinate stored in a column of the data of a plot
+#[derive(Clone, Copy)]
+enum Coordinate {
+    X,
+    Y,
+    /// Distance to the `X` column on both sides, like half the length of an error bar
+    XDelta,
+    /// Distance to the `Y` column on both sides, like half the height of an error bar
+    YDelta,
+    /// A value that's mapped to the size or the color of a point
+    Value,
 }
 
 #[derive(Clone)]
 struct Plot {
+    axes: Axes,
+    broken: bool,
+    caps: Option<Caps>,
+    columns: &'static [Coordinate],
     data: Matrix,
+    label: Option<Text>,
+    needs_color: bool,
+    needs_line_width: bool,
     script: String,
+    shares_color: bool,
 }
 
 impl Plot {
-    fn new<S>(data: Matrix, script: &S) -> Plot
+    fn new<S>(axes: Axes, columns: &'static [Coordinate], data: Matrix, script: &S) -> Plot
     where
         S: Script,
     {
         Plot {
+            axes,
+            broken: false,
+            caps: None,
+            columns,
             data,
+            label: script.label().cloned(),
+            needs_color: script.needs_color(),
+            needs_line_width: script.needs_line_width(),
             script: script.script(),
+            shares_color: false,
         }
     }
 
+    /// Makes the plot take the color that the figure gave to the previous plot
+    fn sharing_color(mut self, shares_color: bool) -> Plot {
+        self.shares_color = shares_color;
+        self
+    }
+
     fn data(&self) -> &Matrix {
         &self.data
     }
@@ -838,7 +1529,7 @@ impl ::std::error::Error for VersionError {
         }
     }
 
-    fn cause(&self) -> Option<&dyn (::std::error::Error)> {
+    fn cause(&self) -> Option<&dyn ::std::error::Error> {
         match self {
             VersionError::Exec(err) => Some(err),
             _ => None,
@@ -846,6 +1537,41 @@ impl ::std::error::Error for VersionError {
     }
 }
 
+/// Issues found in the data of a figure, see [`Figure::warnings`]
+///
+/// [`Figure::warnings`]: struct.Figure.html#method.warnings
+#[derive(Debug)]
+pub enum Warning {
+    /// Points with missing values weren't plotted
+    MissingValues {
+        /// Number of points that won't be drawn
+        points: usize,
+    },
+    /// Non-positive values have been plotted against a logarithmic axis, gnuplot won't draw them
+    NonPositiveOnLogScale {
+        /// The logarithmic axis
+        axis: Axis,
+        /// Number of non-positive values
+        points: usize,
+    },
+}
+
+impl fmt::Display for Warning {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Warning::MissingValues { points } => {
+                write!(f, "{} point(s) with missing values won't be drawn", points)
+            }
+            Warning::NonPositiveOnLogScale { axis, points } => write!(
+                f,
+                "{} non-positive value(s) plotted against the logarithmic {} axis won't be drawn",
+                points,
+                axis.display()
+            ),
+        }
+    }
+}
+
 /// Structure representing a gnuplot version number.
 pub struct Version {
     /// The major version number
@@ -888,27 +1614,12 @@ fn parse_version(version_str: &str) -> Result<Version, Option<ParseIntError>> {
 }
 
 fn scale_factor(map: &map::axis::Map<AxisProperties>, axes: Axes) -> (f64, f64) {
-    use crate::Axes::*;
-    use crate::Axis::*;
-
-    match axes {
-        BottomXLeftY => (
-            map.get(BottomX).map_or(1., |props| props.scale_factor()),
-            map.get(LeftY).map_or(1., |props| props.scale_factor()),
-        ),
-        BottomXRightY => (
-            map.get(BottomX).map_or(1., |props| props.scale_factor()),
-            map.get(RightY).map_or(1., |props| props.scale_factor()),
-        ),
-        TopXLeftY => (
-            map.get(TopX).map_or(1., |props| props.scale_factor()),
-            map.get(LeftY).map_or(1., |props| props.scale_factor()),
-        ),
-        TopXRightY => (
-            map.get(TopX).map_or(1., |props| props.scale_factor()),
-            map.get(RightY).map_or(1., |props| props.scale_factor()),
-        ),
-    }
+    let (x, y) = axes.split();
+
+    (
+        map.get(x).map_or(1., |props| props.scale_factor()),
+        map.get(y).map_or(1., |props| props.scale_factor()),
+    )
 }
 
 // XXX :-1: to intra-crate privacy rules
@@ -920,6 +1631,61 @@ trait ScaleFactorTrait {
 
 #[cfg(test)]
 mod test {
+    use crate::prelude::*;
+    use crate::Warning;
+
+    #[test]
+    fn non_positive_data_on_log_scale() {
+        let mut figure = Figure::new();
+        figure
+            .configure_axis(Axis::LeftY, |a| a.scale(Scale::LogarithmicBase(2.)))
+            .plot(
+                Lines {
+                    x: &[1., 2., 3., 4.],
+                    y: &[-1., 0., 1., 2.],
+                },
+                |l| l,
+            );
+
+        match &figure.warnings()[..] {
+            [Warning::NonPositiveOnLogScale { axis, points }] => {
+                assert_eq!(Axis::LeftY, *axis);
+                assert_eq!(2, *points);
+            }
+            _ => panic!("expected a single warning"),
+        }
+    }
+
+    #[test]
+    fn warnings_are_reported_when_dumping() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        static REPORTED: AtomicUsize = AtomicUsize::new(0);
+
+        let mut figure = Figure::new();
+        figure
+            .on_warning(|_| {
+                REPORTED.fetch_add(1, Ordering::SeqCst);
+            })
+            .plot(
+                Lines {
+                    x: &[1., 2.],
+                    y: &[Some(1.), None],
+                },
+                |l| l,
+            )
+            .dump(&mut Vec::new())
+            .unwrap();
+
+        assert_eq!(1, REPORTED.load(Ordering::SeqCst));
+    }
+
+    #[test]
+    #[should_panic]
+    fn too_many_dashes() {
+        LineType::Custom(vec![(1., 1.); 5]).check();
+    }
+
     #[test]
     fn version() {
         if let Ok(version) = super::version() {
@@ -932,7 +1698,7 @@ mod test {
     #[test]
     fn test_parse_version_on_valid_string() {
         let string = "gnuplot 5.0 patchlevel 7";
-        let version = super::parse_version(&string).unwrap();
+        let version = super::parse_version(string).unwrap();
         assert_eq!(5, version.major);
         assert_eq!(0, version.minor);
         assert_eq!("7", &version.patch);
@@ -941,7 +1707,7 @@ mod test {
     #[test]
     fn test_parse_gentoo_version() {
         let string = "gnuplot 5.2 patchlevel 5a (Gentoo revision r0)";
-        let version = super::parse_version(&string).unwrap();
+        let version = super::parse_version(string).unwrap();
         assert_eq!(5, version.major);
         assert_eq!(2, version.minor);
         assert_eq!("5a", &version.patch);
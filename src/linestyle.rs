@@ -0,0 +1,178 @@
+//! Reusable line styles
+
+use crate::{Color, Default, Display, LineType, PointType, Script};
+
+/// Line properties defined once on a figure (`set style line`) and referenced by several plots
+///
+/// ```
+/// use ploteria::prelude::*;
+///
+/// let mut figure = Figure::new();
+/// let baseline = figure.line_style(|ls| {
+///     ls.color(Color::Black)
+///         .line_type(LineType::Custom(vec![(6., 3.)]))
+///         .line_width(2.)
+/// });
+///
+/// figure
+///     .plot(Lines { x: &[0, 1], y: &[1, 1] }, |c| c.line_style(baseline))
+///     .plot(Lines { x: &[0, 1], y: &[2, 2] }, |c| c.line_style(baseline));
+/// ```
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(feature = "serde", serde(default = "Default::default"))]
+pub struct LineStyle {
+    color: Option<Color>,
+    #[cfg_attr(feature = "serde", serde(deserialize_with = "crate::de::checked"))]
+    line_type: Option<LineType>,
+    #[cfg_attr(feature = "serde", serde(deserialize_with = "crate::de::positive"))]
+    linewidth: Option<f64>,
+    #[cfg_attr(feature = "serde", serde(deserialize_with = "crate::de::positive"))]
+    point_size: Option<f64>,
+    point_type: Option<PointType>,
+}
+
+/// Handle to a line style of a figure, returned by [`Figure::line_style`]
+///
+/// [`Figure::line_style`]: struct.Figure.html#method.line_style
+#[derive(Clone, Copy)]
+pub struct LineStyleId(usize);
+
+impl LineStyle {
+    /// Changes the color of the line
+    pub fn color(&mut self, color: Color) -> &mut LineStyle {
+        self.color = Some(color);
+        self
+    }
+
+    /// Changes the line type
+    ///
+    /// # Panics
+    ///
+    /// Panics if a `Custom` dash pattern is invalid
+    pub fn line_type(&mut self, lt: LineType) -> &mut LineStyle {
+        lt.check();
+
+        self.line_type = Some(lt);
+        self
+    }
+
+    /// Changes the width of the line
+    ///
+    /// # Panics
+    ///
+    /// Panics if `width` is a non-positive value
+    pub fn line_width(&mut self, lw: f64) -> &mut LineStyle {
+        assert!(lw > 0.);
+
+        self.linewidth = Some(lw);
+        self
+    }
+
+    /// Changes the size of the points
+    ///
+    /// # Panics
+    ///
+    /// Panics if `size` is a non-positive value
+    pub fn point_size(&mut self, ps: f64) -> &mut LineStyle {
+        assert!(ps > 0.);
+
+        self.point_size = Some(ps);
+        self
+    }
+
+    /// Changes the point type
+    pub fn point_type(&mut self, pt: PointType) -> &mut LineStyle {
+        self.point_type = Some(pt);
+        self
+    }
+}
+
+impl LineStyleId {
+    /// gnuplot numbers the line styles from 1
+    pub(crate) fn new(index: usize) -> LineStyleId {
+        LineStyleId(index + 1)
+    }
+
+    pub(crate) fn number(self) -> usize {
+        self.0
+    }
+}
+
+impl Default for LineStyle {
+    fn default() -> LineStyle {
+        LineStyle {
+            color: None,
+            line_type: None,
+            linewidth: None,
+            point_size: None,
+            point_type: None,
+        }
+    }
+}
+
+impl Script for (LineStyleId, &LineStyle) {
+    fn script(&self) -> String {
+        let (id, style) = *self;
+        let mut script = format!("set style line {} ", id.number());
+
+        if let Some(ref lt) = style.line_type {
+            script.push_str(&format!("dt {} ", lt.display()))
+        }
+
+        if let Some(lw) = style.linewidth {
+            script.push_str(&format!("lw {} ", lw))
+        }
+
+        if let Some(color) = style.color {
+            script.push_str(&format!("lc rgb '{}' ", color.display()))
+        }
+
+        if let Some(pt) = style.point_type {
+            script.push_str(&format!("pt {} ", pt.display()))
+        }
+
+        if let Some(ps) = style.point_size {
+            script.push_str(&format!("ps {} ", ps))
+        }
+
+        script.truncate(script.trim_end().len());
+        script.push('\n');
+        script
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::prelude::*;
+
+    #[test]
+    fn plots_share_line_styles() {
+        let mut figure = Figure::new();
+        let dashed = figure.line_style(|ls| {
+            ls.color(Color::Black)
+                .line_type(LineType::Custom(vec![(6., 3.), (1., 3.)]))
+                .line_width(2.)
+        });
+        figure
+            .plot(
+                Lines {
+                    x: &[1., 2.],
+                    y: &[1., 2.],
+                },
+                |l| l.line_style(dashed),
+            )
+            .plot(
+                Lines {
+                    x: &[1., 2.],
+                    y: &[2., 3.],
+                },
+                |l| l.line_style(dashed).line_type(LineType::Dot),
+            );
+        let script = figure.script();
+
+        assert!(script.contains("set style line 1 dt (6,3,1,3) lw 2 lc rgb 'black'\n"));
+        assert!(script.contains("with lines ls 1 notitle"));
+        assert!(script.contains("with lines ls 1 dt (1,4) notitle"));
+    }
+}
@@ -63,7 +63,7 @@ pub mod axis {
             old
         }
 
-        pub fn iter(&self) -> Items<T> {
+        pub fn iter(&self) -> Items<'_, T> {
             Items {
                 map: self,
                 state: Some(Axis::BottomX),
@@ -84,4 +84,72 @@ pub mod axis {
             ])
         }
     }
+
+    /// Serialized as a map from the axes to their values
+    #[cfg(feature = "serde")]
+    impl<T> serde::Serialize for Map<T>
+    where
+        T: serde::Serialize,
+    {
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: serde::Serializer,
+        {
+            serializer.collect_map(self.iter())
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    impl<'de, T> serde::Deserialize<'de> for Map<T>
+    where
+        T: serde::Deserialize<'de>,
+    {
+        fn deserialize<D>(deserializer: D) -> Result<Map<T>, D::Error>
+        where
+            D: serde::Deserializer<'de>,
+        {
+            use std::fmt;
+            use std::marker::PhantomData;
+
+            struct Visitor<T>(PhantomData<T>);
+
+            impl<'de, T> serde::de::Visitor<'de> for Visitor<T>
+            where
+                T: serde::Deserialize<'de>,
+            {
+                type Value = Map<T>;
+
+                fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                    f.write_str("a map from axes to values")
+                }
+
+                fn visit_map<A>(self, mut access: A) -> Result<Map<T>, A::Error>
+                where
+                    A: serde::de::MapAccess<'de>,
+                {
+                    use serde::de::Error;
+
+                    const AXES: &[&str] = &["BottomX", "LeftY", "RightY", "TopX"];
+                    let mut map = Map::new();
+
+                    // Some formats only have string keys, so the axes are matched by name
+                    while let Some((name, value)) = access.next_entry::<String, T>()? {
+                        let axis = match &*name {
+                            "BottomX" => Axis::BottomX,
+                            "LeftY" => Axis::LeftY,
+                            "RightY" => Axis::RightY,
+                            "TopX" => Axis::TopX,
+                            _ => return Err(A::Error::unknown_variant(&name, AXES)),
+                        };
+
+                        map.insert(axis, value);
+                    }
+
+                    Ok(map)
+                }
+            }
+
+            deserializer.deserialize_map(Visitor(PhantomData))
+        }
+    }
 }
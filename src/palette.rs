@@ -0,0 +1,193 @@
+//! Color palettes
+
+use std::num::NonZeroUsize;
+
+use crate::Color;
+
+/// Sets of colors that tell the plots of a figure apart
+///
+/// Used by [`Figure::color_cycle`] and [`Theme::colors`].
+///
+/// [`Figure::color_cycle`]: struct.Figure.html#method.color_cycle
+/// [`Theme::colors`]: struct.Theme.html#method.colors
+#[derive(Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub enum Palette {
+    /// The 8 colors of Okabe and Ito, which are distinguishable with any form of color blindness
+    OkabeIto,
+    /// The 10 colors of Tableau
+    Tableau10,
+    /// `n` evenly spaced samples of the viridis color map, from dark blue to yellow, see
+    /// [`Palette::viridis`]
+    ///
+    /// **Note** viridis is perceptually uniform, which suits series that follow an order (e.g.
+    /// increasing input sizes)
+    ///
+    /// [`Palette::viridis`]: enum.Palette.html#method.viridis
+    Viridis(NonZeroUsize),
+}
+
+const OKABE_ITO: [Color; 8] = [
+    Color::Rgb(0x00, 0x00, 0x00),
+    Color::Rgb(0xe6, 0x9f, 0x00),
+    Color::Rgb(0x56, 0xb4, 0xe9),
+    Color::Rgb(0x00, 0x9e, 0x73),
+    Color::Rgb(0xf0, 0xe4, 0x42),
+    Color::Rgb(0x00, 0x72, 0xb2),
+    Color::Rgb(0xd5, 0x5e, 0x00),
+    Color::Rgb(0xcc, 0x79, 0xa7),
+];
+
+const TABLEAU_10: [Color; 10] = [
+    Color::Rgb(0x4e, 0x79, 0xa7),
+    Color::Rgb(0xf2, 0x8e, 0x2b),
+    Color::Rgb(0xe1, 0x57, 0x59),
+    Color::Rgb(0x76, 0xb7, 0xb2),
+    Color::Rgb(0x59, 0xa1, 0x4f),
+    Color::Rgb(0xed, 0xc9, 0x48),
+    Color::Rgb(0xb0, 0x7a, 0xa1),
+    Color::Rgb(0xff, 0x9d, 0xa7),
+    Color::Rgb(0x9c, 0x75, 0x5f),
+    Color::Rgb(0xba, 0xb0, 0xac),
+];
+
+/// The viridis color map, sampled every tenth of its range
+const VIRIDIS: [(u8, u8, u8); 11] = [
+    (0x44, 0x01, 0x54),
+    (0x48, 0x24, 0x75),
+    (0x41, 0x44, 0x87),
+    (0x35, 0x5f, 0x8d),
+    (0x2a, 0x78, 0x8e),
+    (0x21, 0x91, 0x8c),
+    (0x22, 0xa8, 0x84),
+    (0x44, 0xbf, 0x70),
+    (0x7a, 0xd1, 0x51),
+    (0xbd, 0xdf, 0x26),
+    (0xfd, 0xe7, 0x25),
+];
+
+impl Palette {
+    /// `n` evenly spaced samples of the viridis color map
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is zero
+    pub fn viridis(n: usize) -> Palette {
+        Palette::Viridis(NonZeroUsize::new(n).expect("a viridis palette needs samples"))
+    }
+
+    /// The colors of the palette
+    pub fn colors(self) -> Vec<Color> {
+        match self {
+            Palette::OkabeIto => OKABE_ITO.to_vec(),
+            Palette::Tableau10 => TABLEAU_10.to_vec(),
+            Palette::Viridis(n) => {
+                let n = n.get();
+
+                (0..n)
+                    .map(|i| {
+                        viridis(if n == 1 {
+                            0.
+                        } else {
+                            i as f64 / (n - 1) as f64
+                        })
+                    })
+                    .collect()
+            }
+        }
+    }
+}
+
+/// The viridis color map at `t`, within `[0, 1]`
+fn viridis(t: f64) -> Color {
+    let position = t * (VIRIDIS.len() - 1) as f64;
+    let i = (position as usize).min(VIRIDIS.len() - 2);
+    let fraction = position - i as f64;
+
+    let (r0, g0, b0) = VIRIDIS[i];
+    let (r1, g1, b1) = VIRIDIS[i + 1];
+    let mix = |low: u8, high: u8| {
+        (f64::from(low) + fraction * (f64::from(high) - f64::from(low))).round() as u8
+    };
+
+    Color::Rgb(mix(r0, r1), mix(g0, g1), mix(b0, b1))
+}
+
+#[cfg(test)]
+mod test {
+    use crate::prelude::*;
+    use crate::Display;
+
+    #[test]
+    fn viridis_samples() {
+        let colors = Palette::viridis(3)
+            .colors()
+            .iter()
+            .map(|color| color.display())
+            .collect::<Vec<_>>();
+
+        assert_eq!(vec!["#440154", "#21918c", "#fde725"], colors);
+        assert_eq!(8, Palette::OkabeIto.colors().len());
+        assert_eq!(10, Palette::Tableau10.colors().len());
+    }
+
+    #[test]
+    #[should_panic]
+    fn empty_viridis() {
+        Palette::viridis(0);
+    }
+
+    #[test]
+    fn plots_without_a_color_cycle_through_the_palette() {
+        let mut figure = Figure::new();
+        figure
+            .plot(
+                Lines {
+                    x: &[1., 2.],
+                    y: &[1., 2.],
+                },
+                |l| l,
+            )
+            .plot(
+                ErrorBand {
+                    x: &[1., 2.],
+                    y: &[1., 2.],
+                    low: &[0., 1.],
+                    high: &[2., 3.],
+                },
+                |b| b,
+            )
+            .plot(
+                Candlesticks {
+                    x: &[1.],
+                    whisker_min: &[0.],
+                    box_min: &[1.],
+                    box_high: &[2.],
+                    whisker_high: &[3.],
+                },
+                |c| c.color(Color::Red),
+            )
+            .plot(
+                FilledCurve {
+                    x: &[1., 2.],
+                    y1: &[0., 0.],
+                    y2: &[1., 2.],
+                },
+                |f| f,
+            );
+
+        let colors = figure
+            .script()
+            .split("lc rgb '")
+            .skip(1)
+            .map(|s| s[..s.find('\'').unwrap()].to_owned())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            vec!["dark-violet", "#009e73", "#009e73", "red", "#56b4e9"],
+            colors
+        );
+
+        figure.color_cycle(Palette::viridis(3).colors());
+        assert!(figure.script().contains("lc rgb '#440154'"));
+    }
+}
@@ -1,10 +1,23 @@
 //! A collection of the most used traits, structs and enums
 
-pub use crate::axis::{Axes, Axis, Range, Scale, TicLabels};
+pub use crate::axis::{Axes, Axis, Range, Scale, TicFormat, TicLabels};
 pub use crate::candlestick::Candlesticks;
-pub use crate::curve::Curve::{Dots, Impulses, Lines, LinesPoints, Points, Steps};
+pub use crate::curve::Curve::{
+    Dots, FSteps, FillSteps, HiSteps, Impulses, Lines, LinesPoints, Points, Steps,
+};
+pub use crate::curve::Smooth;
+pub use crate::errorband::ErrorBand;
 pub use crate::errorbar::ErrorBar::{XErrorBars, XErrorLines, YErrorBars, YErrorLines};
-pub use crate::filledcurve::FilledCurve;
+pub use crate::errorbar::SymmetricErrorBar::{
+    XSymmetricErrorBars, XSymmetricErrorLines, YSymmetricErrorBars, YSymmetricErrorLines,
+};
+pub use crate::errorbar::XyErrorBar::{BoxXyError, XyErrorBars, XyErrorLines};
+pub use crate::filledcurve::{FilledArea, FilledCurve};
 pub use crate::key::{Horizontal, Justification, Order, Position, Stacked, Vertical};
+pub use crate::scatter::Scatter::{Bubbles, ColoredPoints};
+pub use crate::stackedarea::StackedArea;
 pub use crate::traits::Plot;
-pub use crate::{Color, Figure, LineType, PointType, Terminal};
+pub use crate::{
+    Caps, Color, Column, Downsampling, Figure, Layer, LineStyle, LineType, MissingValues, Palette,
+    PointType, RichText, Terminal, Text, Theme,
+};
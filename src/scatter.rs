@@ -0,0 +1,324 @@
+//! Scatter plots whose points encode a third series
+
+use itertools::izip;
+use std::iter::IntoIterator;
+
+use crate::data::Matrix;
+use crate::traits::{self, Data};
+use crate::{
+    Axes, Color, Column, Coordinate, Default, Display, Figure, MissingValues, Plot, PointType,
+    Script, Text,
+};
+
+/// Properties common to scatter plots
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(feature = "serde", serde(default = "Default::default"))]
+pub struct Properties {
+    axes: Option<Axes>,
+    color: Option<Color>,
+    label: Option<Text>,
+    missing_values: Option<MissingValues>,
+    #[cfg_attr(feature = "serde", serde(deserialize_with = "crate::de::positive"))]
+    point_size: Option<f64>,
+    point_type: Option<PointType>,
+}
+
+impl Properties {
+    /// Select the axes to plot against
+    ///
+    /// **Note** By default, the `BottomXLeftY` axes are used
+    pub fn axes(&mut self, axes: Axes) -> &mut Properties {
+        self.axes = Some(axes);
+        self
+    }
+
+    /// Sets the color of the points
+    ///
+    /// **Note** `ColoredPoints` ignore this property, their colors come from the color map of
+    /// the figure
+    pub fn color(&mut self, color: Color) -> &mut Properties {
+        self.color = Some(color);
+        self
+    }
+
+    /// Sets the legend label
+    pub fn label<S>(&mut self, label: S) -> &mut Properties
+    where
+        S: Into<Text>,
+    {
+        self.label = Some(label.into());
+        self
+    }
+
+    /// Changes how the missing (`NaN`, infinite or `None`) values of the series are handled
+    ///
+    /// **Note** By default, the policy configured on the figure is used
+    ///
+    /// **Note** With `MissingValues::Error`, a series that contains missing values makes drawing,
+    /// dumping or saving the figure fail
+    pub fn missing_values(&mut self, policy: MissingValues) -> &mut Properties {
+        self.missing_values = Some(policy);
+        self
+    }
+
+    /// Changes the size of the points, `Bubbles` multiply their sizes by this factor
+    ///
+    /// # Panics
+    ///
+    /// Panics if `size` is a non-positive value
+    pub fn point_size(&mut self, ps: f64) -> &mut Properties {
+        assert!(ps > 0.);
+
+        self.point_size = Some(ps);
+        self
+    }
+
+    /// Changes the point type
+    ///
+    /// **Note** By default, gnuplot picks the point type
+    pub fn point_type(&mut self, pt: PointType) -> &mut Properties {
+        self.point_type = Some(pt);
+        self
+    }
+}
+
+impl Default for Properties {
+    fn default() -> Properties {
+        Properties {
+            axes: None,
+            color: None,
+            label: None,
+            missing_values: None,
+            point_size: None,
+            point_type: None,
+        }
+    }
+}
+
+impl Script for (Style, &Properties) {
+    fn label(&self) -> Option<&Text> {
+        self.1.label.as_ref()
+    }
+
+    fn needs_color(&self) -> bool {
+        match self.0 {
+            Style::Bubbles => self.1.color.is_none(),
+            Style::ColoredPoints => false,
+        }
+    }
+
+    fn script(&self) -> String {
+        let (style, properties) = *self;
+
+        let mut script = if let Some(axes) = properties.axes {
+            format!("axes {} ", axes.display())
+        } else {
+            String::new()
+        };
+
+        script.push_str("with points ");
+
+        if let Some(pt) = properties.point_type {
+            script.push_str(&format!("pt {} ", pt.display()))
+        }
+
+        match style {
+            Style::Bubbles => {
+                script.push_str("ps variable ");
+
+                if let Some(color) = properties.color {
+                    script.push_str(&format!("lc rgb '{}' ", color.display()))
+                }
+            }
+            Style::ColoredPoints => {
+                if let Some(ps) = properties.point_size {
+                    script.push_str(&format!("ps {} ", ps))
+                }
+
+                script.push_str("lc palette ");
+            }
+        }
+
+        script
+    }
+}
+
+/// Types of scatter plots
+pub enum Scatter<X, Y, Z> {
+    /// A point on each data point, whose size is given by `size` (a.k.a. bubble chart)
+    Bubbles {
+        /// X coordinate of the data points
+        x: X,
+        /// Y coordinate of the data points
+        y: Y,
+        /// Size of the points, relative to the default point size
+        size: Z,
+    },
+    /// A point on each data point, colored by mapping `z` to the color map of the figure
+    ///
+    /// See [`Figure::color_map`]
+    ///
+    /// [`Figure::color_map`]: ../struct.Figure.html#method.color_map
+    ColoredPoints {
+        /// X coordinate of the data points
+        x: X,
+        /// Y coordinate of the data points
+        y: Y,
+        /// Value that picks the color of the points
+        z: Z,
+    },
+}
+
+#[derive(Clone, Copy)]
+enum Style {
+    Bubbles,
+    ColoredPoints,
+}
+
+impl<X, Y, Z> traits::Plot<Scatter<X, Y, Z>> for Figure
+where
+    X: IntoIterator,
+    X::Item: Data,
+    Y: IntoIterator,
+    Y::Item: Data,
+    Z: IntoIterator,
+    Z::Item: Data,
+{
+    type Properties = Properties;
+
+    fn plot<F>(&mut self, scatter: Scatter<X, Y, Z>, configure: F) -> &mut Figure
+    where
+        F: FnOnce(&mut Properties) -> &mut Properties,
+    {
+        let (style, x, y, z) = scatter.into_data();
+
+        let mut props: Properties = Default::default();
+        configure(&mut props);
+
+        let axes = props.axes.unwrap_or(crate::Axes::BottomXLeftY);
+        let (x_factor, y_factor) = crate::scale_factor(&self.axes, axes);
+        let z_factor = style.z_factor(&props);
+
+        let data = Matrix::new(izip!(x, y, z), (x_factor, y_factor, z_factor));
+        self.push(
+            Plot::new(axes, &COLUMNS, data, &(style, &props)),
+            props.missing_values,
+        )
+    }
+}
+
+/// Fast path for large owned or static series: the figure keeps the columns instead of a copy
+impl traits::Plot<Scatter<Column, Column, Column>> for Figure {
+    type Properties = Properties;
+
+    fn plot<F>(&mut self, scatter: Scatter<Column, Column, Column>, configure: F) -> &mut Figure
+    where
+        F: FnOnce(&mut Properties) -> &mut Properties,
+    {
+        let (style, Column(x), Column(y), Column(z)) = scatter.into_data();
+
+        let mut props: Properties = Default::default();
+        configure(&mut props);
+
+        let axes = props.axes.unwrap_or(crate::Axes::BottomXLeftY);
+        let (x_factor, y_factor) = crate::scale_factor(&self.axes, axes);
+        let z_factor = style.z_factor(&props);
+
+        let data = Matrix::streamed(vec![(x, x_factor), (y, y_factor), (z, z_factor)]);
+        self.push(
+            Plot::new(axes, &COLUMNS, data, &(style, &props)),
+            props.missing_values,
+        )
+    }
+}
+
+impl<X, Y, Z> Scatter<X, Y, Z> {
+    fn into_data(self) -> (Style, X, Y, Z) {
+        match self {
+            Scatter::Bubbles { x, y, size } => (Style::Bubbles, x, y, size),
+            Scatter::ColoredPoints { x, y, z } => (Style::ColoredPoints, x, y, z),
+        }
+    }
+}
+
+impl Style {
+    /// Scale factor of the third series, the sizes of the bubbles are relative to the point size
+    fn z_factor(self, properties: &Properties) -> f64 {
+        match self {
+            Style::Bubbles => properties.point_size.unwrap_or(1.),
+            Style::ColoredPoints => 1.,
+        }
+    }
+}
+
+/// Order in which gnuplot expects the columns of a scatter plot
+const COLUMNS: [Coordinate; 3] = [Coordinate::X, Coordinate::Y, Coordinate::Value];
+
+#[cfg(test)]
+mod test {
+    use crate::prelude::*;
+
+    #[test]
+    fn bubbles_scale_the_sizes() {
+        let mut figure = Figure::new();
+        figure.plot(
+            Bubbles {
+                x: &[1., 2.],
+                y: &[1., 2.],
+                size: &[1., 3.],
+            },
+            |b| b.point_size(2.).point_type(PointType::Glyph('★')),
+        );
+        let script = figure.script();
+
+        assert!(script.contains("with points pt '★' ps variable lc rgb 'dark-violet' notitle"));
+        assert_eq!(figure.data_range(Axis::LeftY), Some((1., 2.)));
+    }
+
+    #[test]
+    fn colored_points_use_the_color_map() {
+        let mut figure = Figure::new();
+        figure.color_map(Palette::viridis(2)).plot(
+            ColoredPoints {
+                x: &[1., 2.],
+                y: &[1., 2.],
+                z: &[0., 10.],
+            },
+            |p| p.color(Color::Red).point_type(PointType::FilledDiamond),
+        );
+        let script = figure.script();
+
+        assert!(script.contains("set palette defined (0 '#440154', 1 '#fde725')\n"));
+        assert!(script.contains("with points pt 13 lc palette notitle"));
+        assert!(!script.contains("'red'"));
+    }
+
+    #[test]
+    fn columns_and_iterators_produce_the_same_plot() {
+        let (x, y, size) = (vec![1., 2.], vec![1., 2.], vec![1., 3.]);
+
+        let mut iterators = Figure::new();
+        iterators.plot(
+            Bubbles {
+                x: &x,
+                y: &y,
+                size: &size,
+            },
+            |b| b.point_size(2.),
+        );
+        let mut columns = Figure::new();
+        columns.plot(
+            Bubbles {
+                x: Column::from(x),
+                y: Column::from(y),
+                size: Column::from(size),
+            },
+            |b| b.point_size(2.),
+        );
+
+        let (mut expected, mut actual) = (Vec::new(), Vec::new());
+        iterators.dump(&mut expected).unwrap();
+        columns.dump(&mut actual).unwrap();
+        assert_eq!(expected, actual);
+    }
+}
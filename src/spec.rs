@@ -0,0 +1,815 @@
+//! Declarative figures
+//!
+//! A [`Spec`] describes a figure and its plots as a JSON or TOML document:
+//!
+//! ```toml
+//! [figure]
+//! title = "Throughput"
+//!
+//! [figure.axes.BottomX]
+//! label = "Threads"
+//!
+//! [[plots]]
+//! kind = "LinesPoints"
+//! x = [1, 2, 4, 8]
+//! y = "mean"
+//! properties = { color = "DarkViolet", label = "mean" }
+//! ```
+//!
+//! The data of a plot is either given inline, or names a column that's supplied when the figure
+//! is built, see [`Spec::into_figure`].
+//!
+//! Titles and labels are plain strings, or tables for [`Text::raw`] (`{ raw = "x_{1}" }`) and
+//! [`RichText`] (`{ rich = [{ text = "t" }, { sub = "exec" }] }`).
+//!
+//! [`RichText`]: ../struct.RichText.html
+//! [`Spec`]: struct.Spec.html
+//! [`Spec::into_figure`]: struct.Spec.html#method.into_figure
+//! [`Text::raw`]: ../struct.Text.html#method.raw
+
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::candlestick::{self, Candlesticks};
+use crate::curve::{self, Curve};
+use crate::errorband::{self, ErrorBand};
+use crate::errorbar::{self, ErrorBar, SymmetricErrorBar, XyErrorBar};
+use crate::filledcurve::{self, FilledCurve};
+use crate::scatter::{self, Scatter};
+use crate::stackedarea::{self, StackedArea};
+use crate::traits::Plot;
+use crate::Figure;
+
+/// Values of a series, `None` marks a missing value
+type Series = Vec<Option<f64>>;
+
+/// Format of a spec document
+#[derive(Clone, Copy)]
+pub enum Format {
+    /// JSON
+    Json,
+    /// TOML
+    Toml,
+}
+
+/// A figure along with the plots drawn on it
+#[derive(Deserialize, Serialize)]
+pub struct Spec {
+    /// Settings of the figure: title, axes, key, output, etc
+    #[serde(default = "Figure::new")]
+    pub figure: Figure,
+    /// Plots, in drawing order
+    #[serde(default)]
+    pub plots: Vec<PlotSpec>,
+}
+
+impl Spec {
+    /// Parses a spec document
+    pub fn parse(spec: &str, format: Format) -> Result<Spec, SpecError> {
+        match format {
+            Format::Json => serde_json::from_str(spec).map_err(SpecError::Json),
+            Format::Toml => toml::from_str(spec).map_err(SpecError::Toml),
+        }
+    }
+
+    /// Builds the figure, the plots that name their data get it from `columns`
+    pub fn into_figure(self, columns: &HashMap<String, Series>) -> Result<Figure, SpecError> {
+        let mut figure = self.figure;
+
+        for plot in self.plots {
+            plot.add_to(&mut figure, columns)?;
+        }
+
+        Ok(figure)
+    }
+}
+
+/// The data of a plot
+#[derive(Deserialize, Serialize)]
+#[serde(untagged)]
+pub enum Values {
+    /// Name of a column of the data the figure is built with
+    Column(String),
+    /// The values themselves, `null` marks a missing value
+    Inline(Series),
+}
+
+impl Values {
+    fn resolve(self, columns: &HashMap<String, Series>) -> Result<Series, SpecError> {
+        match self {
+            Values::Column(name) => match columns.get(&name) {
+                Some(values) => Ok(values.clone()),
+                None => Err(SpecError::UnknownColumn(name)),
+            },
+            Values::Inline(values) => Ok(values),
+        }
+    }
+}
+
+/// A plot, selected by its `kind`
+#[derive(Deserialize, Serialize)]
+#[serde(tag = "kind")]
+#[allow(missing_docs)]
+pub enum PlotSpec {
+    Dots(CurveSpec),
+    FSteps(CurveSpec),
+    FillSteps(CurveSpec),
+    HiSteps(CurveSpec),
+    Impulses(CurveSpec),
+    Lines(CurveSpec),
+    LinesPoints(CurveSpec),
+    Points(CurveSpec),
+    Steps(CurveSpec),
+    XErrorBars(XErrorBarSpec),
+    XErrorLines(XErrorBarSpec),
+    YErrorBars(YErrorBarSpec),
+    YErrorLines(YErrorBarSpec),
+    XSymmetricErrorBars(XSymmetricErrorBarSpec),
+    XSymmetricErrorLines(XSymmetricErrorBarSpec),
+    YSymmetricErrorBars(YSymmetricErrorBarSpec),
+    YSymmetricErrorLines(YSymmetricErrorBarSpec),
+    XyErrorBars(XyErrorBarSpec),
+    XyErrorLines(XyErrorBarSpec),
+    BoxXyError(XyErrorBarSpec),
+    Candlesticks(CandlesticksSpec),
+    FilledCurve(FilledCurveSpec),
+    ErrorBand(ErrorBandSpec),
+    Bubbles(BubblesSpec),
+    ColoredPoints(ColoredPointsSpec),
+    StackedArea(StackedAreaSpec),
+}
+
+impl PlotSpec {
+    fn add_to(
+        self,
+        figure: &mut Figure,
+        columns: &HashMap<String, Series>,
+    ) -> Result<(), SpecError> {
+        let data = |values: Values| values.resolve(columns);
+        let lines = matches!(
+            self,
+            PlotSpec::XErrorLines(_)
+                | PlotSpec::YErrorLines(_)
+                | PlotSpec::XSymmetricErrorLines(_)
+                | PlotSpec::YSymmetricErrorLines(_)
+        );
+
+        match self {
+            PlotSpec::Dots(spec) => spec.add_to(figure, columns, |x, y| Curve::Dots { x, y })?,
+            PlotSpec::FSteps(spec) => {
+                spec.add_to(figure, columns, |x, y| Curve::FSteps { x, y })?
+            }
+            PlotSpec::FillSteps(spec) => {
+                spec.add_to(figure, columns, |x, y| Curve::FillSteps { x, y })?
+            }
+            PlotSpec::HiSteps(spec) => {
+                spec.add_to(figure, columns, |x, y| Curve::HiSteps { x, y })?
+            }
+            PlotSpec::Impulses(spec) => {
+                spec.add_to(figure, columns, |x, y| Curve::Impulses { x, y })?
+            }
+            PlotSpec::Lines(spec) => spec.add_to(figure, columns, |x, y| Curve::Lines { x, y })?,
+            PlotSpec::LinesPoints(spec) => {
+                spec.add_to(figure, columns, |x, y| Curve::LinesPoints { x, y })?
+            }
+            PlotSpec::Points(spec) => {
+                spec.add_to(figure, columns, |x, y| Curve::Points { x, y })?
+            }
+            PlotSpec::Steps(spec) => spec.add_to(figure, columns, |x, y| Curve::Steps { x, y })?,
+            PlotSpec::XErrorBars(spec) | PlotSpec::XErrorLines(spec) => {
+                let (x, y) = (data(spec.x)?, data(spec.y)?);
+                let (x_low, x_high) = (data(spec.x_low)?, data(spec.x_high)?);
+                let plot = if lines {
+                    ErrorBar::XErrorLines {
+                        x,
+                        y,
+                        x_low,
+                        x_high,
+                    }
+                } else {
+                    ErrorBar::XErrorBars {
+                        x,
+                        y,
+                        x_low,
+                        x_high,
+                    }
+                };
+
+                let properties = spec.properties;
+                figure.plot(plot, |p| configure_error_bar(p, properties));
+            }
+            PlotSpec::YErrorBars(spec) | PlotSpec::YErrorLines(spec) => {
+                let (x, y) = (data(spec.x)?, data(spec.y)?);
+                let (y_low, y_high) = (data(spec.y_low)?, data(spec.y_high)?);
+                let plot = if lines {
+                    ErrorBar::YErrorLines {
+                        x,
+                        y,
+                        y_low,
+                        y_high,
+                    }
+                } else {
+                    ErrorBar::YErrorBars {
+                        x,
+                        y,
+                        y_low,
+                        y_high,
+                    }
+                };
+
+                let properties = spec.properties;
+                figure.plot(plot, |p| configure_error_bar(p, properties));
+            }
+            PlotSpec::XSymmetricErrorBars(spec) | PlotSpec::XSymmetricErrorLines(spec) => {
+                let (x, y, x_delta) = (data(spec.x)?, data(spec.y)?, data(spec.x_delta)?);
+                let plot = if lines {
+                    SymmetricErrorBar::XSymmetricErrorLines { x, y, x_delta }
+                } else {
+                    SymmetricErrorBar::XSymmetricErrorBars { x, y, x_delta }
+                };
+
+                let properties = spec.properties;
+                figure.plot(plot, |p| configure_error_bar(p, properties));
+            }
+            PlotSpec::YSymmetricErrorBars(spec) | PlotSpec::YSymmetricErrorLines(spec) => {
+                let (x, y, y_delta) = (data(spec.x)?, data(spec.y)?, data(spec.y_delta)?);
+                let plot = if lines {
+                    SymmetricErrorBar::YSymmetricErrorLines { x, y, y_delta }
+                } else {
+                    SymmetricErrorBar::YSymmetricErrorBars { x, y, y_delta }
+                };
+
+                let properties = spec.properties;
+                figure.plot(plot, |p| configure_error_bar(p, properties));
+            }
+            PlotSpec::XyErrorBars(spec) => {
+                spec.add_to(figure, columns, |x, y, x_low, x_high, y_low, y_high| {
+                    XyErrorBar::XyErrorBars {
+                        x,
+                        y,
+                        x_low,
+                        x_high,
+                        y_low,
+                        y_high,
+                    }
+                })?
+            }
+            PlotSpec::XyErrorLines(spec) => {
+                spec.add_to(figure, columns, |x, y, x_low, x_high, y_low, y_high| {
+                    XyErrorBar::XyErrorLines {
+                        x,
+                        y,
+                        x_low,
+                        x_high,
+                        y_low,
+                        y_high,
+                    }
+                })?
+            }
+            PlotSpec::BoxXyError(spec) => {
+                spec.add_to(figure, columns, |x, y, x_low, x_high, y_low, y_high| {
+                    XyErrorBar::BoxXyError {
+                        x,
+                        y,
+                        x_low,
+                        x_high,
+                        y_low,
+                        y_high,
+                    }
+                })?
+            }
+            PlotSpec::Candlesticks(spec) => {
+                let plot = Candlesticks {
+                    x: data(spec.x)?,
+                    whisker_min: data(spec.whisker_min)?,
+                    box_min: data(spec.box_min)?,
+                    box_high: data(spec.box_high)?,
+                    whisker_high: data(spec.whisker_high)?,
+                };
+
+                let properties = spec.properties;
+                figure.plot(plot, |p| configure(p, properties));
+            }
+            PlotSpec::FilledCurve(spec) => {
+                let plot = FilledCurve {
+                    x: data(spec.x)?,
+                    y1: data(spec.y1)?,
+                    y2: data(spec.y2)?,
+                };
+
+                let properties = spec.properties;
+                figure.plot(plot, |p| configure(p, properties));
+            }
+            PlotSpec::ErrorBand(spec) => {
+                let plot = ErrorBand {
+                    x: data(spec.x)?,
+                    y: data(spec.y)?,
+                    low: data(spec.low)?,
+                    high: data(spec.high)?,
+                };
+
+                let properties = spec.properties;
+                figure.plot(plot, |p| configure(p, properties));
+            }
+            PlotSpec::Bubbles(spec) => {
+                let plot = Scatter::Bubbles {
+                    x: data(spec.x)?,
+                    y: data(spec.y)?,
+                    size: data(spec.size)?,
+                };
+
+                let properties = spec.properties;
+                figure.plot(plot, |p| configure(p, properties));
+            }
+            PlotSpec::ColoredPoints(spec) => {
+                let plot = Scatter::ColoredPoints {
+                    x: data(spec.x)?,
+                    y: data(spec.y)?,
+                    z: data(spec.z)?,
+                };
+
+                let properties = spec.properties;
+                figure.plot(plot, |p| configure(p, properties));
+            }
+            PlotSpec::StackedArea(spec) => {
+                let plot = StackedArea {
+                    x: data(spec.x)?,
+                    series: spec
+                        .series
+                        .into_iter()
+                        .map(data)
+                        .collect::<Result<Vec<_>, _>>()?,
+                };
+
+                let properties = spec.properties;
+                figure.plot(plot, |p| configure(p, properties));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn configure<P>(properties: &mut P, spec: Option<P>) -> &mut P {
+    if let Some(spec) = spec {
+        *properties = spec;
+    }
+
+    properties
+}
+
+fn configure_error_bar(
+    properties: &mut errorbar::Properties,
+    spec: Option<errorbar::Properties>,
+) -> &mut errorbar::Properties {
+    match spec {
+        Some(spec) => properties.replace(spec),
+        None => properties,
+    }
+}
+
+/// Data and properties of a curve
+#[derive(Deserialize, Serialize)]
+pub struct CurveSpec {
+    /// X coordinate of the data points
+    pub x: Values,
+    /// Y coordinate of the data points
+    pub y: Values,
+    /// Properties of the curve
+    #[serde(default)]
+    pub properties: Option<curve::Properties>,
+}
+
+impl CurveSpec {
+    fn add_to<F>(
+        self,
+        figure: &mut Figure,
+        columns: &HashMap<String, Series>,
+        curve: F,
+    ) -> Result<(), SpecError>
+    where
+        F: FnOnce(Series, Series) -> Curve<Series, Series>,
+    {
+        let curve = curve(self.x.resolve(columns)?, self.y.resolve(columns)?);
+        let properties = self.properties;
+
+        figure.plot(curve, |p| match properties {
+            Some(spec) => p.replace(spec),
+            None => p,
+        });
+        Ok(())
+    }
+}
+
+/// Data and properties of horizontal error bars
+#[derive(Deserialize, Serialize)]
+pub struct XErrorBarSpec {
+    /// X coordinate of the data points
+    pub x: Values,
+    /// Y coordinate of the data points
+    pub y: Values,
+    /// X coordinate of the left end of the error bars
+    pub x_low: Values,
+    /// X coordinate of the right end of the error bars
+    pub x_high: Values,
+    /// Properties of the error bars
+    #[serde(default)]
+    pub properties: Option<errorbar::Properties>,
+}
+
+/// Data and properties of vertical error bars
+#[derive(Deserialize, Serialize)]
+pub struct YErrorBarSpec {
+    /// X coordinate of the data points
+    pub x: Values,
+    /// Y coordinate of the data points
+    pub y: Values,
+    /// Y coordinate of the bottom of the error bars
+    pub y_low: Values,
+    /// Y coordinate of the top of the error bars
+    pub y_high: Values,
+    /// Properties of the error bars
+    #[serde(default)]
+    pub properties: Option<errorbar::Properties>,
+}
+
+/// Data and properties of symmetric horizontal error bars
+#[derive(Deserialize, Serialize)]
+pub struct XSymmetricErrorBarSpec {
+    /// X coordinate of the data points
+    pub x: Values,
+    /// Y coordinate of the data points
+    pub y: Values,
+    /// Half the length of the error bars
+    pub x_delta: Values,
+    /// Properties of the error bars
+    #[serde(default)]
+    pub properties: Option<errorbar::Properties>,
+}
+
+/// Data and properties of symmetric vertical error bars
+#[derive(Deserialize, Serialize)]
+pub struct YSymmetricErrorBarSpec {
+    /// X coordinate of the data points
+    pub x: Values,
+    /// Y coordinate of the data points
+    pub y: Values,
+    /// Half the height of the error bars
+    pub y_delta: Values,
+    /// Properties of the error bars
+    #[serde(default)]
+    pub properties: Option<errorbar::Properties>,
+}
+
+/// Data and properties of horizontal and vertical error bars, or of error boxes
+#[derive(Deserialize, Serialize)]
+pub struct XyErrorBarSpec {
+    /// X coordinate of the data points
+    pub x: Values,
+    /// Y coordinate of the data points
+    pub y: Values,
+    /// X coordinate of the left end of the horizontal error bars
+    pub x_low: Values,
+    /// X coordinate of the right end of the horizontal error bars
+    pub x_high: Values,
+    /// Y coordinate of the bottom of the vertical error bars
+    pub y_low: Values,
+    /// Y coordinate of the top of the vertical error bars
+    pub y_high: Values,
+    /// Properties of the error bars
+    #[serde(default)]
+    pub properties: Option<errorbar::Properties>,
+}
+
+impl XyErrorBarSpec {
+    fn add_to<F>(
+        self,
+        figure: &mut Figure,
+        columns: &HashMap<String, Series>,
+        error_bar: F,
+    ) -> Result<(), SpecError>
+    where
+        F: FnOnce(
+            Series,
+            Series,
+            Series,
+            Series,
+            Series,
+            Series,
+        ) -> XyErrorBar<Series, Series, Series, Series, Series, Series>,
+    {
+        let error_bar = error_bar(
+            self.x.resolve(columns)?,
+            self.y.resolve(columns)?,
+            self.x_low.resolve(columns)?,
+            self.x_high.resolve(columns)?,
+            self.y_low.resolve(columns)?,
+            self.y_high.resolve(columns)?,
+        );
+        let properties = self.properties;
+
+        figure.plot(error_bar, |p| configure_error_bar(p, properties));
+        Ok(())
+    }
+}
+
+/// Data and properties of candlesticks
+#[derive(Deserialize, Serialize)]
+pub struct CandlesticksSpec {
+    /// X coordinate of the candlesticks
+    pub x: Values,
+    /// Y coordinate of the end point of the bottom whiskers
+    pub whisker_min: Values,
+    /// Y coordinate of the bottom of the boxes
+    pub box_min: Values,
+    /// Y coordinate of the top of the boxes
+    pub box_high: Values,
+    /// Y coordinate of the end point of the top whiskers
+    pub whisker_high: Values,
+    /// Properties of the candlesticks
+    #[serde(default)]
+    pub properties: Option<candlestick::Properties>,
+}
+
+/// Data and properties of a filled curve
+#[derive(Deserialize, Serialize)]
+pub struct FilledCurveSpec {
+    /// X coordinate of the data points of both curves
+    pub x: Values,
+    /// Y coordinate of the data points of the first curve
+    pub y1: Values,
+    /// Y coordinate of the data points of the second curve
+    pub y2: Values,
+    /// Properties of the filled curve
+    #[serde(default)]
+    pub properties: Option<filledcurve::Properties>,
+}
+
+/// Data and properties of an error band
+#[derive(Deserialize, Serialize)]
+pub struct ErrorBandSpec {
+    /// X coordinate of the data points
+    pub x: Values,
+    /// Y coordinate of the data points of the center line
+    pub y: Values,
+    /// Y coordinate of the bottom of the band
+    pub low: Values,
+    /// Y coordinate of the top of the band
+    pub high: Values,
+    /// Properties of the error band
+    #[serde(default)]
+    pub properties: Option<errorband::Properties>,
+}
+
+/// Data and properties of a bubble chart
+#[derive(Deserialize, Serialize)]
+pub struct BubblesSpec {
+    /// X coordinate of the data points
+    pub x: Values,
+    /// Y coordinate of the data points
+    pub y: Values,
+    /// Size of the points, relative to the default point size
+    pub size: Values,
+    /// Properties of the points
+    #[serde(default)]
+    pub properties: Option<scatter::Properties>,
+}
+
+/// Data and properties of points colored by the color map of the figure
+#[derive(Deserialize, Serialize)]
+pub struct ColoredPointsSpec {
+    /// X coordinate of the data points
+    pub x: Values,
+    /// Y coordinate of the data points
+    pub y: Values,
+    /// Value that picks the color of the points
+    pub z: Values,
+    /// Properties of the points
+    #[serde(default)]
+    pub properties: Option<scatter::Properties>,
+}
+
+/// Data and properties of stacked areas
+#[derive(Deserialize, Serialize)]
+pub struct StackedAreaSpec {
+    /// X coordinate of the data points of all the series
+    pub x: Values,
+    /// Y coordinate of the data points of each series, from the bottom layer to the top one
+    pub series: Vec<Values>,
+    /// Properties of the stacked areas
+    #[serde(default)]
+    pub properties: Option<stackedarea::Properties>,
+}
+
+/// Possible errors when building a figure from a spec
+#[derive(Debug)]
+pub enum SpecError {
+    /// The JSON document is invalid
+    Json(serde_json::Error),
+    /// The TOML document is invalid
+    Toml(toml::de::Error),
+    /// A plot names a column that isn't part of the data
+    UnknownColumn(String),
+}
+
+impl fmt::Display for SpecError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SpecError::Json(err) => write!(f, "invalid JSON spec: {}", err),
+            SpecError::Toml(err) => write!(f, "invalid TOML spec: {}", err),
+            SpecError::UnknownColumn(name) => write!(f, "unknown data column `{}`", name),
+        }
+    }
+}
+
+impl Error for SpecError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            SpecError::Json(err) => Some(err),
+            SpecError::Toml(err) => Some(err),
+            SpecError::UnknownColumn(_) => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashMap;
+
+    use super::{Format, Spec};
+
+    const SPEC: &str = r#"
+        [figure]
+        title = "Throughput"
+        size = [800, 600]
+
+        [figure.axes.BottomX]
+        label = "Threads"
+        scale = "Logarithmic"
+
+        [figure.axes.LeftY]
+        label = { rich = [{ text = "t" }, { sub = "exec" }, { text = " (μs)" }] }
+
+        [[plots]]
+        kind = "LinesPoints"
+        x = [1, 2, 4]
+        y = "mean"
+        properties = { color = "DarkViolet", label = "mean" }
+
+        [[plots]]
+        kind = "YErrorBars"
+        x = [1, 2, 4]
+        y = "mean"
+        y_low = [0.5, 1.5, 3.5]
+        y_high = [1.5, 2.5, 4.5]
+    "#;
+
+    #[test]
+    fn toml() {
+        let mut columns = HashMap::new();
+        columns.insert(String::from("mean"), vec![Some(1.), Some(2.), Some(4.)]);
+
+        let figure = Spec::parse(SPEC, Format::Toml)
+            .unwrap()
+            .into_figure(&columns)
+            .unwrap();
+        let script = figure.script();
+
+        assert!(script.contains("set title 'Throughput'"));
+        assert!(script.contains("set xlabel 'Threads'"));
+        assert!(script.contains("set ylabel 't_{exec} (μs)'"));
+        assert!(script.contains("set logscale x"));
+        assert!(script.contains("with linespoints lc rgb 'dark-violet' title 'mean'"));
+        assert!(script.contains("with yerrorbars "));
+    }
+
+    #[test]
+    fn every_kind_of_plot() {
+        let spec = r#"
+            [[plots]]
+            kind = "YSymmetricErrorLines"
+            x = [1, 2]
+            y = [1, 2]
+            y_delta = [0.5, 0.5]
+
+            [[plots]]
+            kind = "BoxXyError"
+            x = [1, 2]
+            y = [1, 2]
+            x_low = [0.5, 1.5]
+            x_high = [1.5, 2.5]
+            y_low = [0, 1]
+            y_high = [2, 3]
+
+            [[plots]]
+            kind = "Bubbles"
+            x = [1, 2]
+            y = [1, 2]
+            size = [1, 3]
+
+            [[plots]]
+            kind = "ColoredPoints"
+            x = [1, 2]
+            y = [1, 2]
+            z = [0, 10]
+
+            [[plots]]
+            kind = "StackedArea"
+            x = [1, 2]
+            series = [[1, 1], "load"]
+            properties = { labels = ["idle", "busy"] }
+        "#;
+        let mut columns = HashMap::new();
+        columns.insert(String::from("load"), vec![Some(2.), Some(3.)]);
+
+        let script = Spec::parse(spec, Format::Toml)
+            .unwrap()
+            .into_figure(&columns)
+            .unwrap()
+            .script();
+
+        assert!(script.contains("with yerrorlines "));
+        assert!(script.contains("with boxxyerror "));
+        assert!(script.contains("ps variable "));
+        assert!(script.contains("lc palette "));
+        assert!(script.contains("title 'busy'"));
+    }
+
+    #[test]
+    fn unknown_columns_are_reported() {
+        let spec = Spec::parse(SPEC, Format::Toml).unwrap();
+
+        assert!(spec.into_figure(&HashMap::new()).is_err());
+    }
+
+    #[test]
+    fn specs_survive_a_json_round_trip() {
+        let mut columns = HashMap::new();
+        columns.insert(String::from("mean"), vec![Some(1.), None, Some(4.)]);
+        let spec = Spec::parse(SPEC, Format::Toml).unwrap();
+
+        let json = serde_json::to_string(&spec).unwrap();
+        let copy = Spec::parse(&json, Format::Json).unwrap();
+
+        assert_eq!(
+            spec.into_figure(&columns).unwrap().script(),
+            copy.into_figure(&columns).unwrap().script()
+        );
+    }
+
+    #[test]
+    fn invalid_values_are_rejected() {
+        let invalid = [
+            r#"{ "figure": { "axes": { "BottomX": { "scale": { "LogarithmicBase": 1 } } } } }"#,
+            r#"{ "figure": { "axes": { "LeftY": { "range": { "Padded": -0.1 } } } } }"#,
+            r#"{ "figure": { "axes": { "LeftY": { "major_grid": { "opacity": 2 } } } } }"#,
+            r#"{ "figure": { "caps": { "Size": -1 } } }"#,
+            r#"{ "figure": { "colors": [] } }"#,
+            r#"{ "figure": { "color_map": { "Viridis": 0 } } }"#,
+            r#"{ "figure": { "theme": { "line_width": 0 } } }"#,
+            r#"{ "figure": { "title": { "rich": [{ "sized": { "text": "t", "size": 0 } }] } } }"#,
+            r#"{ "plots": [{ "kind": "Lines", "x": [1], "y": [1],
+                "properties": { "linewidth": -3, "point_size": -1 } }] }"#,
+            r#"{ "plots": [{ "kind": "Lines", "x": [1], "y": [1],
+                "properties": { "line_type": { "Custom": [] } } }] }"#,
+            r#"{ "plots": [{ "kind": "FilledCurve", "x": [1], "y1": [1], "y2": [1],
+                "properties": { "pattern": 8 } }] }"#,
+        ];
+
+        for spec in &invalid {
+            assert!(Spec::parse(spec, Format::Json).is_err(), "{}", spec);
+        }
+
+        let valid = [
+            r#"{ "figure": { "title": { "rich": [{ "sized": { "text": "t", "size": 12 } }] } } }"#,
+            r#"{ "figure": { "color_map": { "Viridis": 5 } } }"#,
+            r#"{ "plots": [{ "kind": "Lines", "x": [1], "y": [1],
+                "properties": { "linewidth": 2, "line_type": { "Custom": [[2, 1]] } } }] }"#,
+        ];
+
+        for spec in &valid {
+            assert!(Spec::parse(spec, Format::Json).is_ok(), "{}", spec);
+        }
+    }
+
+    #[test]
+    fn figures_dont_take_raw_plot_scripts() {
+        let spec = r#"{
+            "figure": {
+                "plots": [{ "script": "with lines\nprint system('id')\n#" }],
+                "tics": { "BottomX": "print system('id')\n" }
+            }
+        }"#;
+
+        let script = Spec::parse(spec, Format::Json)
+            .unwrap()
+            .into_figure(&HashMap::new())
+            .unwrap()
+            .script();
+
+        assert!(!script.contains("system"));
+    }
+}
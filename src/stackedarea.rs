@@ -0,0 +1,273 @@
+//! Stacked area plots
+
+use std::iter::IntoIterator;
+
+use crate::data::Matrix;
+use crate::filledcurve::{self, Mode};
+use crate::traits::{self, Data};
+use crate::{Axes, Coordinate, Default, Figure, Plot, Text};
+
+/// Properties common to stacked area plots
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(feature = "serde", serde(default = "Default::default"))]
+pub struct Properties {
+    axes: Option<Axes>,
+    border: bool,
+    labels: Vec<Text>,
+    normalized: bool,
+    #[cfg_attr(feature = "serde", serde(deserialize_with = "crate::de::fraction"))]
+    opacity: Option<f64>,
+}
+
+impl Properties {
+    /// Select the axes to plot against
+    ///
+    /// **Note** By default, the `BottomXLeftY` axes are used
+    pub fn axes(&mut self, axes: Axes) -> &mut Properties {
+        self.axes = Some(axes);
+        self
+    }
+
+    /// Shows or hides the border around each layer
+    ///
+    /// **Note** By default, the borders are hidden
+    pub fn border(&mut self, show: bool) -> &mut Properties {
+        self.border = show;
+        self
+    }
+
+    /// Sets the legend labels of the layers, in the order of the series
+    ///
+    /// **Note** The layers without a label don't appear in the key
+    pub fn labels<I, S>(&mut self, labels: I) -> &mut Properties
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<Text>,
+    {
+        self.labels = labels.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Stacks the shares of the series (in percent) instead of their values, so the top layer
+    /// always reaches 100
+    ///
+    /// **Note** By default, the values are stacked as is
+    pub fn normalized(&mut self, normalized: bool) -> &mut Properties {
+        self.normalized = normalized;
+        self
+    }
+
+    /// Changes the opacity of the layers
+    ///
+    /// **Note** By default, the layers are totally opaque (`opacity = 1.0`)
+    ///
+    /// # Panics
+    ///
+    /// Panics if `opacity` is outside the range `[0, 1]`
+    pub fn opacity(&mut self, opacity: f64) -> &mut Properties {
+        assert!((0. ..=1.).contains(&opacity));
+
+        self.opacity = Some(opacity);
+        self
+    }
+}
+
+impl Default for Properties {
+    fn default() -> Properties {
+        Properties {
+            axes: None,
+            border: false,
+            labels: Vec::new(),
+            normalized: false,
+            opacity: None,
+        }
+    }
+}
+
+/// Stacks several series on top of each other, each series fills the area between its
+/// cumulative sum and the one of the previous series
+///
+/// Every layer takes the next color of the color cycle of the figure. A missing value only leaves
+/// its own layer out at that point, according to the `MissingValues` policy of the figure, and the
+/// layers above it stack on the layers below it
+///
+/// # Panics
+///
+/// Panics if a series doesn't have one value per `x` coordinate
+pub struct StackedArea<X, S> {
+    /// X coordinate of the data points of all the series
+    pub x: X,
+    /// Y coordinate of the data points of each series, from the bottom layer to the top one
+    pub series: S,
+}
+
+impl<X, S, Y> traits::Plot<StackedArea<X, S>> for Figure
+where
+    X: IntoIterator,
+    X::Item: Data,
+    S: IntoIterator<Item = Y>,
+    Y: IntoIterator,
+    Y::Item: Data,
+{
+    type Properties = Properties;
+
+    fn plot<F>(&mut self, area: StackedArea<X, S>, configure: F) -> &mut Figure
+    where
+        F: FnOnce(&mut Properties) -> &mut Properties,
+    {
+        let StackedArea { x, series } = area;
+
+        let mut props: Properties = Default::default();
+        configure(&mut props);
+
+        let axes = props.axes.unwrap_or(crate::Axes::BottomXLeftY);
+        let (x_factor, y_factor) = crate::scale_factor(&self.axes, axes);
+
+        let x = x.into_iter().map(Data::f64).collect::<Vec<_>>();
+        let mut layers = layers(
+            &x,
+            series
+                .into_iter()
+                .map(|y| y.into_iter().map(Data::f64).collect()),
+        );
+
+        if props.normalized {
+            if let Some((bottom, top)) = layers.last() {
+                // The top of the last layer is missing where its series is, the total is its bottom
+                let totals = bottom
+                    .iter()
+                    .zip(top)
+                    .map(|(&bottom, &top)| if top.is_finite() { top } else { bottom })
+                    .collect::<Vec<_>>();
+
+                for (bottom, top) in &mut layers {
+                    for edge in &mut [bottom, top] {
+                        for (y, &total) in edge.iter_mut().zip(&totals) {
+                            *y = if total == 0. { 0. } else { 100. * *y / total };
+                        }
+                    }
+                }
+            }
+        }
+
+        for (i, (bottom, top)) in layers.iter().enumerate() {
+            let mut layer: filledcurve::Properties = Default::default();
+            layer.axes(axes).border(props.border);
+            if let Some(label) = props.labels.get(i) {
+                layer.label(label.clone());
+            }
+            if let Some(opacity) = props.opacity {
+                layer.opacity(opacity);
+            }
+
+            let data = Matrix::from_columns(&[&x, bottom, top], &[x_factor, y_factor, y_factor]);
+            self.push(
+                Plot::new(
+                    axes,
+                    &[Coordinate::X, Coordinate::Y, Coordinate::Y],
+                    data,
+                    &(Mode::Between, &layer),
+                ),
+                None,
+            );
+        }
+
+        self
+    }
+}
+
+/// Bottom and top edges of the layers stacked from `series`
+///
+/// A missing value (e.g. `NaN`) only leaves the top of its own layer missing, the layers above it
+/// stack on the values below it
+///
+/// # Panics
+///
+/// Panics if a series doesn't have one value per `x` coordinate
+fn layers<I>(x: &[f64], series: I) -> Vec<(Vec<f64>, Vec<f64>)>
+where
+    I: Iterator<Item = Vec<f64>>,
+{
+    let mut total = vec![0.; x.len()];
+
+    series
+        .map(|y| {
+            assert_eq!(
+                y.len(),
+                x.len(),
+                "every series needs one value per x coordinate"
+            );
+
+            let bottom = total.clone();
+            let top = total
+                .iter_mut()
+                .zip(y)
+                .map(|(sum, y)| {
+                    if y.is_finite() {
+                        *sum += y;
+                        *sum
+                    } else {
+                        y
+                    }
+                })
+                .collect();
+
+            (bottom, top)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use crate::prelude::*;
+
+    #[test]
+    fn layers_are_stacked() {
+        let mut figure = Figure::new();
+        figure.plot(
+            StackedArea {
+                x: &[1., 2.],
+                series: vec![vec![1., 3.], vec![1., 1.]],
+            },
+            |s| s.labels(vec!["heap", "stack"]).normalized(true),
+        );
+        let script = figure.script();
+
+        assert_eq!(figure.data_range(Axis::LeftY), Some((0., 100.)));
+        assert!(script.contains("lc rgb 'dark-violet' title 'heap'"));
+        assert!(script.contains("lc rgb '#009e73' title 'stack'"));
+    }
+
+    #[test]
+    fn layers() {
+        let layers = super::layers(&[1., 2.], vec![vec![1., 2.], vec![3., 4.]].into_iter());
+
+        assert_eq!(
+            layers,
+            vec![(vec![0., 0.], vec![1., 2.]), (vec![1., 2.], vec![4., 6.]),]
+        );
+    }
+
+    #[test]
+    fn missing_values_stay_in_their_layer() {
+        let layers = super::layers(
+            &[1., 2.],
+            vec![vec![1., f64::NAN], vec![3., 4.]].into_iter(),
+        );
+
+        assert!(layers[0].1[1].is_nan());
+        assert_eq!(layers[1], (vec![1., 0.], vec![4., 4.]));
+    }
+
+    #[test]
+    #[should_panic]
+    fn short_series() {
+        Figure::new().plot(
+            StackedArea {
+                x: &[1., 2.],
+                series: vec![vec![1.]],
+            },
+            |s| s,
+        );
+    }
+}
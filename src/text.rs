@@ -0,0 +1,480 @@
+//! Strings embedded in the gnuplot scripts
+
+use std::borrow::Cow;
+
+use crate::Terminal;
+
+/// Text shown on the figure: titles, labels, legend entries, etc.
+///
+/// By default the text is shown as is: characters that the terminal treats as markup (e.g.
+/// `@`, `^`, `_`, `{`, `}`, `&`, `~` and `\` for gnuplot's enhanced text) get escaped. Use
+/// [`Text::raw`] to opt into the markup of the terminal, e.g. `Text::raw("x_{1}^2")`, or
+/// [`RichText`] to build text that looks the same on every terminal.
+///
+/// Either way, the text is safely quoted, so it can't break or inject commands in the script.
+///
+/// [`RichText`]: struct.RichText.html
+/// [`Text::raw`]: struct.Text.html#method.raw
+#[derive(Clone)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Deserialize, serde::Serialize),
+    serde(from = "Spec", into = "Spec")
+)]
+pub struct Text(Content);
+
+#[derive(Clone)]
+enum Content {
+    Plain(Cow<'static, str>),
+    Raw(Cow<'static, str>),
+    Rich(Vec<Span>),
+}
+
+impl Text {
+    /// Text that is shown as is
+    pub fn plain<S>(text: S) -> Text
+    where
+        S: Into<Cow<'static, str>>,
+    {
+        Text(Content::Plain(text.into()))
+    }
+
+    /// Text that contains markup, which is passed through to the terminal: enhanced text for
+    /// the `Svg` and `Png` terminals, LaTeX for the `CairoLatex` and `Tikz` terminals
+    pub fn raw<S>(text: S) -> Text
+    where
+        S: Into<Cow<'static, str>>,
+    {
+        Text(Content::Raw(text.into()))
+    }
+
+    /// The text as a gnuplot string expression, marked up for the terminal
+    pub(crate) fn render(&self, markup: Markup) -> String {
+        match self.0 {
+            Content::Plain(ref text) => quote(&escape(text, markup)),
+            Content::Raw(ref text) => quote(text),
+            Content::Rich(ref spans) => quote(
+                &spans
+                    .iter()
+                    .map(|span| span.render(markup))
+                    .collect::<String>(),
+            ),
+        }
+    }
+}
+
+impl From<&'static str> for Text {
+    fn from(text: &'static str) -> Text {
+        Text::plain(text)
+    }
+}
+
+impl From<String> for Text {
+    fn from(text: String) -> Text {
+        Text::plain(text)
+    }
+}
+
+impl From<Cow<'static, str>> for Text {
+    fn from(text: Cow<'static, str>) -> Text {
+        Text::plain(text)
+    }
+}
+
+impl From<RichText> for Text {
+    fn from(text: RichText) -> Text {
+        Text(Content::Rich(text.spans))
+    }
+}
+
+impl<'a> From<&'a mut RichText> for Text {
+    fn from(text: &'a mut RichText) -> Text {
+        Text(Content::Rich(text.spans.clone()))
+    }
+}
+
+/// Text with subscripts, superscripts, bold, italic and resized spans
+///
+/// The text is rendered as enhanced text on the `Svg` and `Png` terminals, and as LaTeX on the
+/// `CairoLatex` and `Tikz` terminals. Greek letters (e.g. `μ`) can be written as is.
+///
+/// ```
+/// use ploteria::RichText;
+///
+/// let mut label = RichText::new();
+/// label.text("t").sub("exec").text(" (μs)");
+/// ```
+#[derive(Clone)]
+pub struct RichText {
+    spans: Vec<Span>,
+}
+
+impl RichText {
+    /// Creates empty text
+    pub fn new() -> RichText {
+        RichText { spans: Vec::new() }
+    }
+
+    /// Appends bold text
+    pub fn bold<S>(&mut self, text: S) -> &mut RichText
+    where
+        S: Into<Cow<'static, str>>,
+    {
+        self.spans.push(Span::Bold(text.into()));
+        self
+    }
+
+    /// Appends italic text
+    pub fn italic<S>(&mut self, text: S) -> &mut RichText
+    where
+        S: Into<Cow<'static, str>>,
+    {
+        self.spans.push(Span::Italic(text.into()));
+        self
+    }
+
+    /// Appends text with a font size of `size` points
+    ///
+    /// # Panics
+    ///
+    /// Panics if `size` is a non-positive value
+    pub fn sized<S>(&mut self, text: S, size: f64) -> &mut RichText
+    where
+        S: Into<Cow<'static, str>>,
+    {
+        assert!(size > 0.);
+
+        self.spans.push(Span::Sized {
+            text: text.into(),
+            size,
+        });
+        self
+    }
+
+    /// Appends a subscript
+    pub fn sub<S>(&mut self, text: S) -> &mut RichText
+    where
+        S: Into<Cow<'static, str>>,
+    {
+        self.spans.push(Span::Sub(text.into()));
+        self
+    }
+
+    /// Appends a superscript
+    pub fn sup<S>(&mut self, text: S) -> &mut RichText
+    where
+        S: Into<Cow<'static, str>>,
+    {
+        self.spans.push(Span::Sup(text.into()));
+        self
+    }
+
+    /// Appends normal text
+    pub fn text<S>(&mut self, text: S) -> &mut RichText
+    where
+        S: Into<Cow<'static, str>>,
+    {
+        self.spans.push(Span::Text(text.into()));
+        self
+    }
+}
+
+/// A run of rich text that shares the same style
+#[derive(Clone)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Deserialize, serde::Serialize),
+    serde(rename_all = "snake_case")
+)]
+enum Span {
+    Bold(Cow<'static, str>),
+    Italic(Cow<'static, str>),
+    Sized {
+        text: Cow<'static, str>,
+        #[cfg_attr(feature = "serde", serde(deserialize_with = "crate::de::positive"))]
+        size: f64,
+    },
+    Sub(Cow<'static, str>),
+    Sup(Cow<'static, str>),
+    Text(Cow<'static, str>),
+}
+
+impl Span {
+    fn render(&self, markup: Markup) -> String {
+        match markup {
+            Markup::Enhanced => match *self {
+                Span::Bold(ref text) => format!("{{/:Bold {}}}", escape(text, markup)),
+                Span::Italic(ref text) => format!("{{/:Italic {}}}", escape(text, markup)),
+                Span::Sized { ref text, size } => {
+                    format!("{{/={} {}}}", size, escape(text, markup))
+                }
+                Span::Sub(ref text) => format!("_{{{}}}", escape(text, markup)),
+                Span::Sup(ref text) => format!("^{{{}}}", escape(text, markup)),
+                Span::Text(ref text) => escape(text, markup),
+            },
+            Markup::Latex => match *self {
+                Span::Bold(ref text) => format!(r"\textbf{{{}}}", escape(text, markup)),
+                Span::Italic(ref text) => format!(r"\textit{{{}}}", escape(text, markup)),
+                Span::Sized { ref text, size } => format!(
+                    r"{{\fontsize{{{}}}{{{:.1}}}\selectfont {}}}",
+                    size,
+                    1.2 * size,
+                    escape(text, markup)
+                ),
+                Span::Sub(ref text) => format!(r"\textsubscript{{{}}}", escape(text, markup)),
+                Span::Sup(ref text) => format!(r"\textsuperscript{{{}}}", escape(text, markup)),
+                Span::Text(ref text) => escape(text, markup),
+            },
+        }
+    }
+}
+
+/// Markup language that the terminal interprets in the text
+#[derive(Clone, Copy)]
+pub(crate) enum Markup {
+    /// gnuplot's enhanced text
+    Enhanced,
+    /// LaTeX, which typesets the text of the `CairoLatex` and `Tikz` terminals
+    Latex,
+}
+
+impl Terminal {
+    /// Markup interpreted by the terminal
+    pub(crate) fn markup(self) -> Markup {
+        match self {
+            Terminal::Png | Terminal::Svg => Markup::Enhanced,
+            Terminal::CairoLatex | Terminal::Tikz => Markup::Latex,
+        }
+    }
+}
+
+/// Serialized form of the text: a plain string, `{ raw = "..." }` or `{ rich = [...] }`
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize, serde::Serialize)]
+#[serde(untagged)]
+enum Spec {
+    Plain(Cow<'static, str>),
+    Raw { raw: Cow<'static, str> },
+    Rich { rich: Vec<Span> },
+}
+
+#[cfg(feature = "serde")]
+impl From<Spec> for Text {
+    fn from(spec: Spec) -> Text {
+        match spec {
+            Spec::Plain(text) => Text::plain(text),
+            Spec::Raw { raw } => Text::raw(raw),
+            Spec::Rich { rich } => Text(Content::Rich(rich)),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl From<Text> for Spec {
+    fn from(text: Text) -> Spec {
+        match text.0 {
+            Content::Plain(text) => Spec::Plain(text),
+            Content::Raw(raw) => Spec::Raw { raw },
+            Content::Rich(rich) => Spec::Rich { rich },
+        }
+    }
+}
+
+/// Escapes the characters of `s` that `markup` would interpret
+pub(crate) fn escape(s: &str, markup: Markup) -> String {
+    let mut escaped = String::with_capacity(s.len());
+
+    for c in s.chars() {
+        match markup {
+            Markup::Enhanced => match c {
+                '\\' | '@' | '^' | '_' | '{' | '}' | '&' | '~' => {
+                    escaped.push('\\');
+                    escaped.push(c);
+                }
+                _ => escaped.push(c),
+            },
+            Markup::Latex => match c {
+                '\\' => escaped.push_str(r"\textbackslash{}"),
+                '^' => escaped.push_str(r"\textasciicircum{}"),
+                '~' => escaped.push_str(r"\textasciitilde{}"),
+                '{' | '}' | '$' | '&' | '#' | '%' | '_' => {
+                    escaped.push('\\');
+                    escaped.push(c);
+                }
+                _ => match greek(c) {
+                    Some(name) => {
+                        escaped.push_str(r"\ensuremath{\");
+                        escaped.push_str(name);
+                        escaped.push('}');
+                    }
+                    None => escaped.push(c),
+                },
+            },
+        }
+    }
+
+    escaped
+}
+
+/// Name of the LaTeX math command that typesets the Greek letter `c`
+fn greek(c: char) -> Option<&'static str> {
+    Some(match c {
+        'α' => "alpha",
+        'β' => "beta",
+        'γ' => "gamma",
+        'δ' => "delta",
+        'ε' => "epsilon",
+        'ζ' => "zeta",
+        'η' => "eta",
+        'θ' => "theta",
+        'ι' => "iota",
+        'κ' => "kappa",
+        'λ' => "lambda",
+        'μ' | 'µ' => "mu",
+        'ν' => "nu",
+        'ξ' => "xi",
+        'π' => "pi",
+        'ρ' => "rho",
+        'σ' => "sigma",
+        'τ' => "tau",
+        'υ' => "upsilon",
+        'φ' => "phi",
+        'χ' => "chi",
+        'ψ' => "psi",
+        'ω' => "omega",
+        'Γ' => "Gamma",
+        'Δ' => "Delta",
+        'Θ' => "Theta",
+        'Λ' => "Lambda",
+        'Ξ' => "Xi",
+        'Π' => "Pi",
+        'Σ' => "Sigma",
+        'Υ' => "Upsilon",
+        'Φ' => "Phi",
+        'Ψ' => "Psi",
+        'Ω' => "Omega",
+        _ => return None,
+    })
+}
+
+/// Quotes `s` as a gnuplot string expression
+///
+/// Single quoted strings are used because gnuplot doesn't expand backslash escapes, macros
+/// (`@`) nor backquoted commands in them. Newlines can't appear inside them, so they are
+/// spliced in as `"\n"`.
+pub(crate) fn quote(s: &str) -> String {
+    let mut quoted = String::with_capacity(s.len() + 2);
+
+    quoted.push('\'');
+    for c in s.chars() {
+        match c {
+            '\'' => quoted.push_str("''"),
+            '\n' => quoted.push_str("'.\"\\n\".'"),
+            '\r' => {}
+            _ => quoted.push(c),
+        }
+    }
+    quoted.push('\'');
+
+    quoted
+}
+
+#[cfg(test)]
+mod test {
+    use super::{quote, Markup, RichText, Text};
+    use crate::prelude::*;
+
+    const ENHANCED: Markup = Markup::Enhanced;
+    const LATEX: Markup = Markup::Latex;
+
+    #[test]
+    fn quotes() {
+        assert_eq!("'it''s fast'", Text::from("it's fast").render(ENHANCED));
+        assert_eq!(
+            "'''; system(''rm -rf ~'') #'",
+            quote("'; system('rm -rf ~') #")
+        );
+    }
+
+    #[test]
+    fn backslashes_and_newlines() {
+        assert_eq!(r"'C:\\bench'", Text::from(r"C:\bench").render(ENHANCED));
+        assert_eq!(r"'C:\bench'", quote(r"C:\bench"));
+        assert_eq!(
+            r#"'first'."\n".'second'"#,
+            Text::from("first\r\nsecond").render(ENHANCED)
+        );
+    }
+
+    #[test]
+    fn enhanced_text_markup() {
+        assert_eq!(
+            r"'\@\^\_\{\}\&\~ `date`'",
+            Text::from("@^_{}&~ `date`").render(ENHANCED)
+        );
+        assert_eq!("'x_{1}^2'", Text::raw("x_{1}^2").render(ENHANCED));
+        assert_eq!(
+            r#"'x_{1}''s'."\n".'2'"#,
+            Text::raw("x_{1}'s\n2").render(ENHANCED)
+        );
+    }
+
+    #[test]
+    fn latex_markup() {
+        assert_eq!(
+            r"'50\% of n\_iter (\ensuremath{\mu}s)'",
+            Text::from("50% of n_iter (μs)").render(LATEX)
+        );
+        assert_eq!(r"'$x_1^2$'", Text::raw("$x_1^2$").render(LATEX));
+    }
+
+    #[test]
+    fn rich_text() {
+        let mut text = RichText::new();
+        text.text("t")
+            .sub("exec")
+            .sup("2")
+            .bold("b_")
+            .italic("i")
+            .sized("s", 8.);
+        let text = Text::from(&mut text);
+
+        assert_eq!(
+            r"'t_{exec}^{2}{/:Bold b\_}{/:Italic i}{/=8 s}'",
+            text.render(ENHANCED)
+        );
+        assert_eq!(
+            concat!(
+                r"'t\textsubscript{exec}\textsuperscript{2}\textbf{b\_}\textit{i}",
+                r"{\fontsize{8}{9.6}\selectfont s}'"
+            ),
+            text.render(LATEX)
+        );
+    }
+
+    #[test]
+    fn labels_are_marked_up_for_the_terminal() {
+        let mut time = RichText::new();
+        time.text("t").sub("exec").text(" (μs)");
+
+        let mut figure = Figure::new();
+        figure
+            .configure_axis(Axis::LeftY, |a| a.label(&mut time))
+            .plot(
+                Lines {
+                    x: &[1., 2.],
+                    y: &[1., 2.],
+                },
+                |l| l.label("n_iter"),
+            );
+        let enhanced = figure.script();
+        assert!(enhanced.contains("set ylabel 't_{exec} (μs)'\n"));
+        assert!(enhanced.contains(r"title 'n\_iter'"));
+
+        figure.terminal(Terminal::CairoLatex).figure_size(360, 216);
+        let latex = figure.script();
+        assert!(latex.contains(r"set ylabel 't\textsubscript{exec} (\ensuremath{\mu}s)'"));
+        assert!(latex.contains(r"title 'n\_iter'"));
+        assert!(latex.contains("set terminal cairolatex pdf size 5in, 3in"));
+    }
+}
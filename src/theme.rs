@@ -0,0 +1,339 @@
+//! Figure themes
+
+use std::borrow::Cow;
+
+use crate::axis::Gridline;
+use crate::key::{Horizontal, Position, Vertical};
+use crate::{Axis, Color, Display, Layer, LineType, Palette};
+
+/// Style shared by several figures: fonts, sizes, colors, gridlines, etc.
+///
+/// A theme is applied with [`Figure::theme`], and only provides defaults: the properties set on
+/// the figure or on its plots take precedence over the theme, regardless of the order in which
+/// they are set.
+///
+/// ```
+/// use ploteria::{Figure, Theme};
+///
+/// let mut theme = Theme::light();
+/// theme.font("Helvetica").font_size(12.).figure_size(1280, 720);
+///
+/// let mut figure = Figure::new();
+/// figure.theme(&theme);
+/// ```
+///
+/// [`Figure::theme`]: struct.Figure.html#method.theme
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(feature = "serde", serde(default = "Theme::new"))]
+pub struct Theme {
+    background: Option<Color>,
+    #[cfg_attr(feature = "serde", serde(deserialize_with = "crate::de::positive"))]
+    border_width: Option<f64>,
+    colors: Vec<Color>,
+    font: Option<Cow<'static, str>>,
+    #[cfg_attr(feature = "serde", serde(deserialize_with = "crate::de::positive"))]
+    font_size: Option<f64>,
+    foreground: Option<Color>,
+    grid: Option<Gridline>,
+    key_position: Option<Position>,
+    #[cfg_attr(feature = "serde", serde(deserialize_with = "crate::de::positive"))]
+    line_width: Option<f64>,
+    size: Option<(usize, usize)>,
+}
+
+impl Theme {
+    /// Creates a theme that leaves everything to gnuplot's defaults
+    pub fn new() -> Theme {
+        Theme {
+            background: None,
+            border_width: None,
+            colors: Vec::new(),
+            font: None,
+            font_size: None,
+            foreground: None,
+            grid: None,
+            key_position: None,
+            line_width: None,
+            size: None,
+        }
+    }
+
+    /// Dark text and a light grid on a white background
+    pub fn light() -> Theme {
+        let mut theme = Theme::new();
+        theme
+            .background(Color::White)
+            .colors(Palette::Tableau10.colors())
+            .foreground(Color::Rgb(0x33, 0x33, 0x33))
+            .grid(|g| {
+                g.color(Color::Rgb(0xdd, 0xdd, 0xdd))
+                    .layer(Layer::Back)
+                    .line_type(LineType::Solid)
+            })
+            .key_position(Position::Inside(Vertical::Top, Horizontal::Right))
+            .line_width(1.5);
+        theme
+    }
+
+    /// Light text and a dim grid on a dark background
+    pub fn dark() -> Theme {
+        let mut theme = Theme::new();
+        theme
+            .background(Color::Rgb(0x22, 0x22, 0x22))
+            .colors(Palette::Tableau10.colors())
+            .foreground(Color::Rgb(0xdd, 0xdd, 0xdd))
+            .grid(|g| {
+                g.color(Color::Rgb(0x44, 0x44, 0x44))
+                    .layer(Layer::Back)
+                    .line_type(LineType::Solid)
+            })
+            .key_position(Position::Inside(Vertical::Top, Horizontal::Right))
+            .line_width(1.5);
+        theme
+    }
+
+    /// Serif fonts, thick lines and no background, for figures embedded in papers
+    ///
+    /// **Note** The figure is 7 by 5 inches, assuming 72 pixels per inch
+    pub fn publication() -> Theme {
+        let mut theme = Theme::new();
+        theme
+            .border_width(1.)
+            .colors(Palette::OkabeIto.colors())
+            .figure_size(504, 360)
+            .font("Times")
+            .font_size(10.)
+            .foreground(Color::Black)
+            .key_position(Position::Inside(Vertical::Top, Horizontal::Left))
+            .line_width(2.);
+        theme
+    }
+
+    /// The `light` theme, with colors that are distinguishable with any form of color blindness
+    pub fn colorblind_safe() -> Theme {
+        let mut theme = Theme::light();
+        theme.colors(Palette::OkabeIto.colors());
+        theme
+    }
+
+    /// Sets the background color of the figure
+    pub fn background(&mut self, color: Color) -> &mut Theme {
+        self.background = Some(color);
+        self
+    }
+
+    /// Changes the width of the border around the plotting area
+    ///
+    /// # Panics
+    ///
+    /// Panics if `width` is a non-positive value
+    pub fn border_width(&mut self, width: f64) -> &mut Theme {
+        assert!(width > 0.);
+
+        self.border_width = Some(width);
+        self
+    }
+
+    /// Changes the colors that are given, in order, to the plots that don't set a color
+    pub fn colors<I>(&mut self, colors: I) -> &mut Theme
+    where
+        I: IntoIterator<Item = Color>,
+    {
+        self.colors = colors.into_iter().collect();
+        self
+    }
+
+    /// Changes the figure size
+    pub fn figure_size(&mut self, width: usize, height: usize) -> &mut Theme {
+        self.size = Some((width, height));
+        self
+    }
+
+    /// Changes the font
+    pub fn font<S>(&mut self, font: S) -> &mut Theme
+    where
+        S: Into<Cow<'static, str>>,
+    {
+        self.font = Some(font.into());
+        self
+    }
+
+    /// Changes the size of the font
+    ///
+    /// # Panics
+    ///
+    /// Panics if `size` is a non-positive value
+    pub fn font_size(&mut self, size: f64) -> &mut Theme {
+        assert!(size > 0.);
+
+        self.font_size = Some(size);
+        self
+    }
+
+    /// Sets the color of the border, the tics and the text
+    pub fn foreground(&mut self, color: Color) -> &mut Theme {
+        self.foreground = Some(color);
+        self
+    }
+
+    /// Shows the major gridlines of the `BottomX` and `LeftY` axes, styled by `configure`
+    ///
+    /// **Note** The gridline properties set on the axes take precedence over the theme's
+    pub fn grid<F: FnOnce(&mut Gridline) -> &mut Gridline>(&mut self, configure: F) -> &mut Theme {
+        let mut grid = self.grid.take().unwrap_or_else(|| {
+            let mut grid = Gridline::new(false);
+            grid.show();
+            grid
+        });
+
+        configure(&mut grid);
+        self.grid = Some(grid);
+        self
+    }
+
+    /// Changes the position of the key
+    pub fn key_position(&mut self, position: Position) -> &mut Theme {
+        self.key_position = Some(position);
+        self
+    }
+
+    /// Changes the width of the lines of the plots that don't set one
+    ///
+    /// # Panics
+    ///
+    /// Panics if `width` is a non-positive value
+    pub fn line_width(&mut self, width: f64) -> &mut Theme {
+        assert!(width > 0.);
+
+        self.line_width = Some(width);
+        self
+    }
+
+    /// Colors given, in order, to the plots that don't set a color
+    pub(crate) fn default_colors(&self) -> Option<&[Color]> {
+        if self.colors.is_empty() {
+            None
+        } else {
+            Some(&self.colors)
+        }
+    }
+
+    pub(crate) fn default_font(&self) -> Option<&Cow<'static, str>> {
+        self.font.as_ref()
+    }
+
+    pub(crate) fn default_font_size(&self) -> Option<f64> {
+        self.font_size
+    }
+
+    /// Gridline of `axis`, only the `BottomX` and `LeftY` axes have one
+    pub(crate) fn default_gridline(&self, axis: Axis) -> Option<&Gridline> {
+        match axis {
+            Axis::BottomX | Axis::LeftY => self.grid.as_ref(),
+            Axis::RightY | Axis::TopX => None,
+        }
+    }
+
+    pub(crate) fn default_key_position(&self) -> Option<Position> {
+        self.key_position
+    }
+
+    pub(crate) fn default_line_width(&self) -> Option<f64> {
+        self.line_width
+    }
+
+    pub(crate) fn default_size(&self) -> Option<(usize, usize)> {
+        self.size
+    }
+
+    /// Settings that aren't tied to any property of the figure: background, border and text color
+    pub(crate) fn script(&self) -> String {
+        let mut script = String::new();
+
+        if let Some(color) = self.background {
+            script.push_str(&format!(
+                "set object rectangle from screen 0,0 to screen 1,1 behind \
+                 fillcolor rgb '{}' fillstyle solid noborder\n",
+                color.display()
+            ));
+        }
+
+        if self.foreground.is_some() || self.border_width.is_some() {
+            script.push_str("set border ");
+
+            if let Some(color) = self.foreground {
+                script.push_str(&format!("lc rgb '{}' ", color.display()));
+            }
+
+            if let Some(width) = self.border_width {
+                script.push_str(&format!("lw {} ", width));
+            }
+
+            script.push('\n');
+        }
+
+        if let Some(color) = self.foreground {
+            let color = color.display();
+
+            for item in &[
+                "tics", "title", "xlabel", "ylabel", "x2label", "y2label", "key",
+            ] {
+                script.push_str(&format!("set {} textcolor rgb '{}'\n", item, color));
+            }
+        }
+
+        script
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::prelude::*;
+
+    #[test]
+    fn themes_provide_the_unset_properties() {
+        let mut theme = Theme::light();
+        theme
+            .font("Helvetica")
+            .font_size(12.)
+            .figure_size(1280, 720);
+
+        let mut figure = Figure::new();
+        figure
+            .theme(&theme)
+            .font_size(14.)
+            .configure_axis(Axis::LeftY, |a| a.configure_major_grid(|g| g.hide()))
+            .plot(
+                Lines {
+                    x: &[1., 2.],
+                    y: &[1., 2.],
+                },
+                |l| l,
+            )
+            .plot(
+                Lines {
+                    x: &[1., 2.],
+                    y: &[2., 3.],
+                },
+                |l| l.color(Color::Red).line_width(1.),
+            )
+            .plot(
+                Points {
+                    x: &[1., 2.],
+                    y: &[3., 4.],
+                },
+                |p| p,
+            );
+        let script = figure.script();
+
+        assert!(script.contains("fillcolor rgb 'white'"));
+        assert!(script.contains("size 1280, 720 font 'Helvetica,14'"));
+        assert!(script.contains("set grid xtics back dt solid lc rgb '#dddddd'\n"));
+        assert!(!script.contains("set grid ytics"));
+        assert!(script.contains("set key on inside top right \n"));
+        assert!(script.contains("lines lw 1.5 lc rgb '#4e79a7' notitle"));
+        assert!(script.contains("lines lw 1 lc rgb 'red' notitle"));
+        assert!(script.contains("points lw 1.5 lc rgb '#f28e2b' notitle"));
+    }
+}